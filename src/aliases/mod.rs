@@ -0,0 +1,100 @@
+use getopts::HasArg;
+
+#[cfg(test)] mod tst;
+
+const END_OF_OPTIONS: &str = "--";
+
+/// An alternative name for a registered option.
+pub struct Alias {
+    /// The alternative name, which is a short name if it is a single character.
+    pub name: String,
+    /// The long name of the option this alias refers to.
+    pub target: String,
+    /// Whether or not the alias is shown in usage, completions and references.
+    pub visible: bool
+}
+
+/// Rewrites every alias in `args` to the long name of the option it refers to.
+/// Options consume values as getopts does according to `has_arg`, leaving them untouched,
+/// as are arguments following `--` and, if `stop_at_free` is set, the first free argument.
+/// Each resolved argument is paired with the index of the argument it was resolved from.
+pub fn resolve<A>(args: Vec<String>, aliases: &[Alias], has_arg: A, stop_at_free: bool) -> Vec<(String, usize)>
+        where A: Fn(&str) -> HasArg {
+    let target = |name: &str| aliases.iter()
+        .find(|alias| alias.name == name)
+        .map(|alias| alias.target.to_string());
+
+    let mut resolved = Vec::new();
+    let mut args = args.into_iter().enumerate().map(|(index, arg)| (arg, index)).peekable();
+    while let Some((arg, index)) = args.next() {
+        let mut consumes_next = false;
+
+        if arg == END_OF_OPTIONS {
+            resolved.push((arg, index));
+            break;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.find('=') {
+                Some(index) => (&long[..index], Some(&long[index..])),
+                None => (long, None)
+            };
+            let name = target(name).unwrap_or_else(|| name.to_string());
+            // Like getopts, long options only take a separate value if it is required
+            consumes_next = value.is_none() && has_arg(&name) == HasArg::Yes;
+            resolved.push((format!("--{}{}", name, value.unwrap_or("")), index));
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            let shorts = &arg[1..];
+            let next_is_value = args.peek().is_some_and(|(next, _)| !is_option(next));
+            if !shorts.chars().any(|c| target(&c.to_string()).is_some()) {
+                // Leave clusters without aliases exactly as they were given
+                let count = shorts.chars().count();
+                consumes_next = match shorts.chars().map(|c| has_arg(&c.to_string())).enumerate()
+                        .find(|&(_, takes)| takes != HasArg::No) {
+                    Some((index, takes)) if index == count - 1 => takes == HasArg::Yes || next_is_value,
+                    _ => false
+                };
+                resolved.push((arg, index));
+            } else {
+                // Split the cluster so aliases may be given in their long form
+                for (offset, c) in shorts.char_indices() {
+                    let rest = &shorts[offset + c.len_utf8()..];
+                    let (token, name) = match target(&c.to_string()) {
+                        Some(name) => (format!("--{}", name), name),
+                        None => (format!("-{}", c), c.to_string())
+                    };
+                    let takes = has_arg(&name);
+                    if takes != HasArg::No {
+                        if rest.is_empty() {
+                            // A short option at the end of a cluster may take the next argument
+                            consumes_next = takes == HasArg::Yes || next_is_value;
+                            if consumes_next && takes == HasArg::Maybe && token.starts_with("--") {
+                                resolved.push((format!("{}={}", token, args.next().unwrap().0), index));
+                                consumes_next = false;
+                            } else {
+                                resolved.push((token, index));
+                            }
+                        } else if token.starts_with("--") {
+                            resolved.push((format!("{}={}", token, rest), index));
+                        } else {
+                            resolved.push((format!("{}{}", token, rest), index));
+                        }
+                        break;
+                    }
+                    resolved.push((token, index));
+                }
+            }
+        } else {
+            resolved.push((arg, index));
+            if stop_at_free { break; }
+        }
+
+        if consumes_next { resolved.extend(args.next()); }
+    }
+
+    resolved.extend(args);
+    resolved
+}
+
+// Private associated methods
+fn is_option(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-')
+}
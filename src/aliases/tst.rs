@@ -0,0 +1,113 @@
+use aliases::Alias;
+use getopts::HasArg;
+
+fn aliases() -> Vec<Alias> {
+    vec!(
+        Alias { name: "old".to_string(), target: "new".to_string(), visible: false },
+        Alias { name: "x".to_string(), target: "new".to_string(), visible: false },
+        Alias { name: "q".to_string(), target: "quiet".to_string(), visible: false },
+        Alias { name: "k".to_string(), target: "color".to_string(), visible: false }
+    )
+}
+
+fn resolve(args: &[&str], stop_at_free: bool) -> Vec<String> {
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    ::aliases::resolve(args, &aliases(), has_arg, stop_at_free).into_iter()
+        .map(|(arg, _)| arg)
+        .collect()
+}
+
+fn has_arg(name: &str) -> HasArg {
+    match name {
+        "new" | "o" => HasArg::Yes,
+        "color" => HasArg::Maybe,
+        _ => HasArg::No
+    }
+}
+
+mod resolve {
+    mod long {
+        use super::super::resolve;
+
+        #[test]
+        fn rewrites_alias() {
+            assert_eq!(vec!("--new", "value"), resolve(&["--old", "value"], false));
+        }
+
+        #[test]
+        fn keeps_attached_value() {
+            assert_eq!(vec!("--new=value"), resolve(&["--old=value"], false));
+        }
+
+        #[test]
+        fn skips_value() {
+            assert_eq!(vec!("--new", "--old"), resolve(&["--new", "--old"], false));
+        }
+    }
+
+    mod short {
+        use super::super::resolve;
+
+        #[test]
+        fn rewrites_alias() {
+            assert_eq!(vec!("--new", "value"), resolve(&["-x", "value"], false));
+        }
+
+        #[test]
+        fn splits_cluster() {
+            assert_eq!(vec!("-v", "--quiet", "--new=value"), resolve(&["-vqxvalue"], false));
+        }
+
+        #[test]
+        fn attaches_separate_maybe_value() {
+            assert_eq!(vec!("--color=never", "file"), resolve(&["-k", "never", "file"], false));
+        }
+
+        #[test]
+        fn leaves_maybe_without_value() {
+            assert_eq!(vec!("--color", "-v"), resolve(&["-k", "-v"], false));
+        }
+
+        #[test]
+        fn leaves_cluster_without_aliases() {
+            assert_eq!(vec!("-vo", "-x"), resolve(&["-vo", "-x"], false));
+        }
+    }
+
+    mod indices {
+        use super::super::{aliases,has_arg};
+
+        #[test]
+        fn point_at_given_arguments() {
+            let args = vec!("-vqx".to_string(), "value".to_string(), "--old".to_string(), "file".to_string());
+            let indices = ::aliases::resolve(args, &aliases(), has_arg, false).into_iter()
+                .map(|(_, index)| index)
+                .collect::<Vec<usize>>();
+
+            assert_eq!(vec!(0, 0, 0, 1, 2, 3), indices);
+        }
+    }
+
+    mod end_of_options {
+        use super::super::resolve;
+
+        #[test]
+        fn stops_resolving() {
+            assert_eq!(vec!("--", "--old"), resolve(&["--", "--old"], false));
+        }
+    }
+
+    mod free {
+        use super::super::resolve;
+
+        #[test]
+        fn continues_resolving() {
+            assert_eq!(vec!("file", "--new"), resolve(&["file", "--old"], false));
+        }
+
+        #[test]
+        fn stops_resolving_if_requested() {
+            assert_eq!(vec!("command", "--old"), resolve(&["command", "--old"], true));
+        }
+    }
+}
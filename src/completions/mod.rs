@@ -0,0 +1,251 @@
+#[cfg(test)] mod tst;
+
+/// The shells for which completion scripts can be generated.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Shell {
+    /// The Bourne Again SHell, via `complete -F`.
+    Bash,
+    /// The friendly interactive shell, via `complete -c`.
+    Fish,
+    /// The Z shell, via `_arguments`.
+    Zsh
+}
+
+/// A command, or subcommand, as seen by the completion generators.
+pub struct Command {
+    /// The names leading to this command, starting with the program name.
+    pub path: Vec<String>,
+    /// The description of the command.
+    pub desc: String,
+    /// The options accepted by the command.
+    pub flags: Vec<Flag>,
+    /// Whether or not the command accepts positional arguments.
+    pub has_positionals: bool,
+    /// The subcommands of the command.
+    pub commands: Vec<Command>
+}
+
+/// An option as seen by the completion generators.
+pub struct Flag {
+    /// The short names of the option.
+    pub shorts: Vec<String>,
+    /// The long names of the option.
+    pub longs: Vec<String>,
+    /// The description of the option.
+    pub desc: String,
+    /// The hint for the value of the option, if it requires one.
+    pub hint: Option<String>,
+    /// The values the option accepts, if they are restricted.
+    pub values: Vec<String>
+}
+
+impl Command {
+    fn function(&self) -> String {
+        let name = self.path.join("_").chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("_{}", name)
+    }
+
+    fn program(&self) -> &str {
+        &self.path[0]
+    }
+
+    fn walk<'a>(&'a self, commands: &mut Vec<&'a Command>) {
+        commands.push(self);
+        for command in &self.commands { command.walk(commands); }
+    }
+}
+
+impl Flag {
+    fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        names.extend(self.shorts.iter().map(|short| format!("-{}", short)));
+        names.extend(self.longs.iter().map(|long| format!("--{}", long)));
+        names
+    }
+}
+
+/// Generates a completion script for `shell` from `command`.
+pub fn generate(command: &Command, shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash(command),
+        Shell::Fish => fish(command),
+        Shell::Zsh => zsh(command)
+    }
+}
+
+// Private associated methods
+fn bash(root: &Command) -> String {
+    let mut commands = Vec::new();
+    root.walk(&mut commands);
+
+    let mut script = format!("{}() {{\n", root.function());
+    script.push_str("    local cur prev cmd word\n");
+    script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    script.push_str(&format!("    cmd=\"{}\"\n", root.function()));
+
+    // Find the deepest subcommand given so far
+    if !root.commands.is_empty() {
+        script.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+        script.push_str("        case \"${cmd},${word}\" in\n");
+        for command in &commands {
+            for subcommand in &command.commands {
+                script.push_str(&format!("            {},{}) cmd=\"{}\" ;;\n",
+                    command.function(), subcommand.path.last().unwrap(), subcommand.function()));
+            }
+        }
+        script.push_str("        esac\n");
+        script.push_str("    done\n");
+    }
+
+    script.push_str("\n    case \"${cmd}\" in\n");
+    for command in &commands {
+        script.push_str(&format!("        {})\n", command.function()));
+
+        let valued = command.flags.iter().filter(|flag| flag.hint.is_some()).collect::<Vec<&Flag>>();
+        if !valued.is_empty() {
+            script.push_str("            case \"${prev}\" in\n");
+            for flag in valued {
+                let reply = if flag.values.is_empty() {
+                    "compgen -f -- \"${cur}\"".to_string()
+                } else {
+                    format!("compgen -W \"{}\" -- \"${{cur}}\"", flag.values.join(" "))
+                };
+                script.push_str(&format!("                {}) COMPREPLY=($({})); return 0 ;;\n",
+                    flag.names().join("|"), reply));
+            }
+            script.push_str("            esac\n");
+        }
+
+        let mut words = command.flags.iter().flat_map(|flag| flag.names()).collect::<Vec<String>>();
+        words.extend(command.commands.iter().map(|subcommand| subcommand.path.last().unwrap().to_string()));
+        let files = if command.has_positionals { "-f " } else { "" };
+        script.push_str(&format!("            COMPREPLY=($(compgen {}-W \"{}\" -- \"${{cur}}\"))\n",
+            files, words.join(" ")));
+        script.push_str("            ;;\n");
+    }
+    script.push_str("    esac\n");
+    script.push_str("}\n\n");
+    script.push_str(&format!("complete -F {} {}\n", root.function(), root.program()));
+    script
+}
+
+fn fish(root: &Command) -> String {
+    let mut commands = Vec::new();
+    root.walk(&mut commands);
+
+    let mut script = String::new();
+    for command in &commands {
+        let condition = fish_condition(command);
+        for subcommand in &command.commands {
+            script.push_str(&format!("complete -c {}{} -f -a {} -d {}\n",
+                root.program(),
+                condition,
+                subcommand.path.last().unwrap(),
+                fish_quote(&subcommand.desc)));
+        }
+        for flag in &command.flags {
+            let mut line = format!("complete -c {}{}", root.program(), condition);
+            for short in &flag.shorts { line.push_str(&format!(" -s {}", short)); }
+            for long in &flag.longs { line.push_str(&format!(" -l {}", long)); }
+            line.push_str(&format!(" -d {}", fish_quote(&flag.desc)));
+            if flag.hint.is_some() { line.push_str(" -r"); }
+            if !flag.values.is_empty() {
+                line.push_str(&format!(" -f -a {}", fish_quote(&flag.values.join(" "))));
+            }
+            script.push_str(&line);
+            script.push('\n');
+        }
+    }
+    script
+}
+
+fn fish_condition(command: &Command) -> String {
+    let mut conditions = command.path[1..].iter()
+        .map(|name| format!("__fish_seen_subcommand_from {}", name))
+        .collect::<Vec<String>>();
+    if !command.commands.is_empty() {
+        let names = command.commands.iter()
+            .map(|subcommand| subcommand.path.last().unwrap().to_string())
+            .collect::<Vec<String>>();
+        conditions.push(format!("not __fish_seen_subcommand_from {}", names.join(" ")));
+    }
+
+    if conditions.is_empty() { String::new() } else { format!(" -n '{}'", conditions.join("; and ")) }
+}
+
+fn fish_quote(string: &str) -> String {
+    format!("'{}'", string.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn zsh(root: &Command) -> String {
+    let mut commands = Vec::new();
+    root.walk(&mut commands);
+
+    let mut script = format!("#compdef {}\n", root.program());
+    for command in &commands {
+        script.push_str(&format!("\n{}() {{\n", command.function()));
+        if !command.commands.is_empty() { script.push_str("    local context state line\n"); }
+        script.push_str("    _arguments -C");
+        for flag in &command.flags {
+            let names = flag.names();
+            let mut spec = if names.len() == 1 {
+                format!("'{}[{}]", names[0], zsh_escape(&flag.desc))
+            } else {
+                format!("'({})'{{{}}}'[{}]", names.join(" "), names.join(","), zsh_escape(&flag.desc))
+            };
+            if let Some(ref hint) = flag.hint {
+                let action = if flag.values.is_empty() {
+                    "_files".to_string()
+                } else {
+                    format!("({})", flag.values.join(" "))
+                };
+                spec.push_str(&format!(":{}:{}", zsh_escape(hint).replace(':', "\\:"), action));
+            }
+            spec.push('\'');
+            script.push_str(&format!(" \\\n        {}", spec));
+        }
+        if !command.commands.is_empty() {
+            script.push_str(&format!(" \\\n        '1: :{}_commands' \\\n        '*:: :->args'\n",
+                command.function()));
+            script.push_str("\n    case $state in\n");
+            script.push_str("        args)\n");
+            script.push_str("            case $line[1] in\n");
+            for subcommand in &command.commands {
+                script.push_str(&format!("                {}) {} ;;\n",
+                    subcommand.path.last().unwrap(), subcommand.function()));
+            }
+            script.push_str("            esac\n");
+            script.push_str("            ;;\n");
+            script.push_str("    esac\n");
+        } else if command.has_positionals {
+            script.push_str(" \\\n        '*: :_files'\n");
+        } else {
+            script.push('\n');
+        }
+        script.push_str("}\n");
+
+        if !command.commands.is_empty() {
+            script.push_str(&format!("\n{}_commands() {{\n", command.function()));
+            script.push_str("    local commands\n");
+            script.push_str("    commands=(\n");
+            for subcommand in &command.commands {
+                script.push_str(&format!("        '{}:{}'\n",
+                    subcommand.path.last().unwrap(), zsh_escape(&subcommand.desc)));
+            }
+            script.push_str("    )\n");
+            script.push_str("    _describe 'command' commands\n");
+            script.push_str("}\n");
+        }
+    }
+    script.push_str(&format!("\n{} \"$@\"\n", root.function()));
+    script
+}
+
+fn zsh_escape(string: &str) -> String {
+    string.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
@@ -0,0 +1,147 @@
+use completions::{Command,Flag};
+
+fn command() -> Command {
+    Command {
+        path: vec!("prog".to_string()),
+        desc: "Run this program".to_string(),
+        flags: vec!(
+            Flag {
+                shorts: vec!("v".to_string()),
+                longs: vec!("verbose".to_string()),
+                desc: "Be verbose".to_string(),
+                hint: None,
+                values: Vec::new()
+            },
+            Flag {
+                shorts: vec!("m".to_string()),
+                longs: vec!("mode".to_string()),
+                desc: "Mode".to_string(),
+                hint: Some("MODE".to_string()),
+                values: vec!("fast".to_string(), "slow".to_string())
+            }
+        ),
+        has_positionals: false,
+        commands: vec!(Command {
+            path: vec!("prog".to_string(), "build".to_string()),
+            desc: "Build the project".to_string(),
+            flags: vec!(
+                Flag {
+                    shorts: vec!("o".to_string()),
+                    longs: vec!("output".to_string()),
+                    desc: "Output file".to_string(),
+                    hint: Some("FILE".to_string()),
+                    values: Vec::new()
+                },
+                Flag {
+                    shorts: Vec::new(),
+                    longs: vec!("color".to_string(), "no-color".to_string()),
+                    desc: "Colorize".to_string(),
+                    hint: None,
+                    values: Vec::new()
+                }
+            ),
+            has_positionals: true,
+            commands: Vec::new()
+        })
+    }
+}
+
+mod generate {
+    mod bash {
+        use completions::{self,Shell};
+        use super::super::command;
+
+        #[test]
+        fn matches_snapshot() {
+            assert_eq!(r#"_prog() {
+    local cur prev cmd word
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="_prog"
+    for word in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        case "${cmd},${word}" in
+            _prog,build) cmd="_prog_build" ;;
+        esac
+    done
+
+    case "${cmd}" in
+        _prog)
+            case "${prev}" in
+                -m|--mode) COMPREPLY=($(compgen -W "fast slow" -- "${cur}")); return 0 ;;
+            esac
+            COMPREPLY=($(compgen -W "-v --verbose -m --mode build" -- "${cur}"))
+            ;;
+        _prog_build)
+            case "${prev}" in
+                -o|--output) COMPREPLY=($(compgen -f -- "${cur}")); return 0 ;;
+            esac
+            COMPREPLY=($(compgen -f -W "-o --output --color --no-color" -- "${cur}"))
+            ;;
+    esac
+}
+
+complete -F _prog prog
+"#, completions::generate(&command(), Shell::Bash));
+        }
+    }
+
+    mod fish {
+        use completions::{self,Shell};
+        use super::super::command;
+
+        #[test]
+        fn matches_snapshot() {
+            assert_eq!(r#"complete -c prog -n 'not __fish_seen_subcommand_from build' -f -a build -d 'Build the project'
+complete -c prog -n 'not __fish_seen_subcommand_from build' -s v -l verbose -d 'Be verbose'
+complete -c prog -n 'not __fish_seen_subcommand_from build' -s m -l mode -d 'Mode' -r -f -a 'fast slow'
+complete -c prog -n '__fish_seen_subcommand_from build' -s o -l output -d 'Output file' -r
+complete -c prog -n '__fish_seen_subcommand_from build' -l color -l no-color -d 'Colorize'
+"#, completions::generate(&command(), Shell::Fish));
+        }
+    }
+
+    mod zsh {
+        use completions::{self,Shell};
+        use super::super::command;
+
+        #[test]
+        fn matches_snapshot() {
+            assert_eq!(r#"#compdef prog
+
+_prog() {
+    local context state line
+    _arguments -C \
+        '(-v --verbose)'{-v,--verbose}'[Be verbose]' \
+        '(-m --mode)'{-m,--mode}'[Mode]:MODE:(fast slow)' \
+        '1: :_prog_commands' \
+        '*:: :->args'
+
+    case $state in
+        args)
+            case $line[1] in
+                build) _prog_build ;;
+            esac
+            ;;
+    esac
+}
+
+_prog_commands() {
+    local commands
+    commands=(
+        'build:Build the project'
+    )
+    _describe 'command' commands
+}
+
+_prog_build() {
+    _arguments -C \
+        '(-o --output)'{-o,--output}'[Output file]:FILE:_files' \
+        '(--color --no-color)'{--color,--no-color}'[Colorize]' \
+        '*: :_files'
+}
+
+_prog "$@"
+"#, completions::generate(&command(), Shell::Zsh));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+use super::{ArgsError,ErrorKind};
+
+#[cfg(test)] mod tst;
+
+/// A single `key = value` assignment read from a configuration file.
+pub struct Entry {
+    pub file: String,
+    pub key: String,
+    pub line: usize,
+    pub section: Option<String>,
+    pub values: Vec<String>
+}
+
+/// Reads and parses the configuration file at `path`.
+pub fn read(path: &Path) -> Result<Vec<Entry>, ArgsError> {
+    let name = path.display().to_string();
+    match fs::read_to_string(path) {
+        Ok(contents) => parse(&name, &contents),
+        Err(error) => Err(ArgsError::new(&name, &error.to_string()).with_kind(ErrorKind::Config))
+    }
+}
+
+/// Parses INI or TOML style `contents`, using `name` to identify the file in errors.
+/// Values may be bare, quoted or arrays of either, and `[section]` headers apply to
+/// the keys that follow them. Lines starting with `#` or `;` are comments.
+pub fn parse(name: &str, contents: &str) -> Result<Vec<Entry>, ArgsError> {
+    let mut entries = Vec::new();
+    let mut section = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        let error = |msg: &str| {
+            ArgsError::new(&format!("{}:{}", name, line_number), msg).with_kind(ErrorKind::Config)
+        };
+
+        // Skip blank lines and comments
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') { continue; }
+
+        if line.starts_with('[') {
+            match line.find(']') {
+                Some(end) if is_comment(&line[end + 1..]) => {
+                    section = Some(line[1..end].trim().to_string());
+                    continue;
+                },
+                _ => { return Err(error(&format!("invalid section header '{}'", line))); }
+            }
+        }
+
+        let (key, value) = match line.find('=') {
+            Some(equals) => (line[..equals].trim(), &line[equals + 1..]),
+            None => { return Err(error(&format!("expected 'key = value' but found '{}'", line))); }
+        };
+        if key.is_empty() { return Err(error("missing key")); }
+
+        let values = parse_value(value).map_err(|msg| {
+            error(&format!("invalid value for key '{}': {}", key, msg))
+        })?;
+        entries.push(Entry {
+            file: name.to_string(),
+            key: key.to_string(),
+            line: line_number,
+            section: section.clone(),
+            values
+        });
+    }
+
+    Ok(entries)
+}
+
+// Private associated methods
+fn is_comment(rest: &str) -> bool {
+    let rest = rest.trim();
+    rest.is_empty() || rest.starts_with('#')
+}
+
+fn parse_value(raw: &str) -> Result<Vec<String>, String> {
+    let mut chars = raw.trim().chars().peekable();
+    let mut values = Vec::new();
+
+    if chars.peek() == Some(&'[') {
+        chars.next();
+        loop {
+            skip_whitespace(&mut chars);
+            if chars.peek() == Some(&']') { chars.next(); break; }
+
+            values.push(parse_scalar(&mut chars, true)?);
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some(',') => {},
+                Some(']') => { break; },
+                _ => { return Err("unterminated array".to_string()); }
+            }
+        }
+    } else {
+        values.push(parse_scalar(&mut chars, false)?);
+    }
+
+    let rest: String = chars.collect();
+    if is_comment(&rest) { Ok(values) } else { Err(format!("unexpected '{}'", rest.trim())) }
+}
+
+fn parse_scalar(chars: &mut Peekable<Chars>, in_array: bool) -> Result<String, String> {
+    let quote = match chars.peek() {
+        Some(&'"') | Some(&'\'') => chars.next(),
+        _ => None
+    };
+
+    let mut value = String::new();
+    match quote {
+        Some(quote) => {
+            loop {
+                match chars.next() {
+                    Some(c) if c == quote => { return Ok(value); },
+                    Some('\\') if quote == '"' => {
+                        match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c @ '"') | Some(c @ '\\') => value.push(c),
+                            Some(c) => { return Err(format!("unknown escape '\\{}'", c)); },
+                            None => { return Err("unterminated string".to_string()); }
+                        }
+                    },
+                    Some(c) => value.push(c),
+                    None => { return Err("unterminated string".to_string()); }
+                }
+            }
+        },
+        None => {
+            // Bare values end at a comment, or at a separator inside of an array
+            while let Some(&c) = chars.peek() {
+                if c == '#' || (in_array && (c == ',' || c == ']')) { break; }
+                value.push(c);
+                chars.next();
+            }
+            Ok(value.trim().to_string())
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) { chars.next(); }
+}
@@ -0,0 +1,72 @@
+mod parse {
+    mod comments {
+        use config;
+
+        #[test]
+        fn are_skipped() {
+            let entries = config::parse("test.ini", "# comment\n; comment\n\nkey = value # trailing\n").unwrap();
+
+            assert_eq!(1, entries.len());
+            assert_eq!("key", entries[0].key);
+            assert_eq!(4, entries[0].line);
+            assert_eq!(vec!("value".to_string()), entries[0].values);
+        }
+    }
+
+    mod quoted {
+        use config;
+
+        #[test]
+        fn returns_unescaped_value() {
+            let entries = config::parse("test.toml", "key = \"a \\\"b\\\" # c\"\nraw = 'd\\e'").unwrap();
+
+            assert_eq!(vec!("a \"b\" # c".to_string()), entries[0].values);
+            assert_eq!(vec!("d\\e".to_string()), entries[1].values);
+        }
+    }
+
+    mod array {
+        use config;
+
+        #[test]
+        fn returns_each_value() {
+            let entries = config::parse("test.toml", "tags = [\"a,b\", c, 'd']").unwrap();
+
+            assert_eq!(vec!("a,b".to_string(), "c".to_string(), "d".to_string()), entries[0].values);
+        }
+    }
+
+    mod section {
+        use config;
+
+        #[test]
+        fn applies_to_following_keys() {
+            let entries = config::parse("test.ini", "top = 1\n[build]\nrelease = true").unwrap();
+
+            assert!(entries[0].section.is_none());
+            assert_eq!(Some("build".to_string()), entries[1].section);
+        }
+    }
+
+    mod missing_equals {
+        use config;
+
+        #[test]
+        fn returns_err_with_location() {
+            let error = config::parse("test.ini", "key = value\nkey value").err().unwrap();
+
+            assert_eq!("test.ini:2: expected 'key = value' but found 'key value'", error.to_string());
+        }
+    }
+
+    mod unterminated_string {
+        use config;
+
+        #[test]
+        fn returns_err_with_key() {
+            let error = config::parse("test.toml", "key = \"value").err().unwrap();
+
+            assert_eq!("test.toml:1: invalid value for key 'key': unterminated string", error.to_string());
+        }
+    }
+}
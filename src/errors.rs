@@ -1,9 +1,64 @@
 use std::error::Error;
 use std::fmt::{self,Debug,Display,Formatter};
+use std::mem;
+use std::slice::{self,Iter};
+use std::vec::IntoIter;
+
+use super::help;
+use super::report::{self,Stream,EX_CONFIG,EX_USAGE};
+
+/// The kind of problem an `ArgsError` describes.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A configuration file could not be read or contained an invalid entry.
+    Config,
+    /// Two options, or members of a group, were given which cannot be used together.
+    Conflict,
+    /// An option was given more often than it may be.
+    DuplicateOption,
+    /// An option which requires an argument was given without one.
+    MissingArgument,
+    /// A required option or positional, or one required by another option, was not given.
+    MissingRequired,
+    /// Several problems were found, which are available from `errors()`.
+    Multiple,
+    /// A value was requested for an option or positional which does not have one.
+    NoValue,
+    /// A value could not be parsed into the requested type.
+    ParseValue,
+    /// A response file could not be read or tokenized.
+    ResponseFile,
+    /// A flag was given an argument or a free argument was not expected.
+    UnexpectedArgument,
+    /// A subcommand was given which is not registered.
+    UnrecognizedCommand,
+    /// An option was given which is not registered.
+    UnrecognizedOption,
+    /// A value was rejected by a `Validation` or is not one of the possible values.
+    Validation,
+    /// Any other problem.
+    Other
+}
 
 /// An implementation of `Error` which may or may not include a scope and/or usage message.
 pub struct ArgsError {
-    desc: String
+    // Boxed to keep `Result<_, ArgsError>` small
+    inner: Box<Inner>
+}
+
+struct Inner {
+    command_line: Vec<String>,
+    errors: Vec<ArgsError>,
+    kind: ErrorKind,
+    location: Option<String>,
+    scope: String,
+    msg: String,
+    opt_name: Option<String>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+    span: Option<(usize, usize)>,
+    usage: Option<String>,
+    value: Option<String>
 }
 
 impl ArgsError {
@@ -16,38 +71,247 @@ impl ArgsError {
     /// Creates a new `ArgsError` with the provided `scope`, `msg` and `usage` message.
     /// If either `scope` or `usage` are an empty string (i.e. `""`) they will be ignored.
     pub fn new_with_usage(scope: &str, msg: &str, usage: &str) -> ArgsError {
-        // If there is a scope, append it to the front
-        let mut desc = if scope.to_string().is_empty() {
-            String::new()
-        } else {
-            format!("{}: ", scope)
+        ArgsError {
+            inner: Box::new(Inner {
+                command_line: Vec::new(),
+                errors: Vec::new(),
+                kind: ErrorKind::Other,
+                location: None,
+                scope: scope.to_string(),
+                msg: msg.to_string(),
+                opt_name: None,
+                source: None,
+                span: None,
+                usage: if usage.is_empty() { None } else { Some(usage.to_string()) },
+                value: None
+            })
+        }
+    }
+
+    /// Creates a new `ArgsError` with the provided `scope` which lists each of `errors`.
+    /// If there is only one error it is returned as is.
+    pub fn multiple(scope: &str, mut errors: Vec<ArgsError>) -> ArgsError {
+        if errors.len() == 1 { return errors.remove(0); }
+
+        let mut msg = format!("{} errors occurred:", errors.len());
+        for error in &errors { msg.push_str(&format!("\n  {}", error)); }
+        let mut error = ArgsError::new(scope, &msg).with_kind(ErrorKind::Multiple);
+        error.inner.errors = errors;
+        error
+    }
+
+    /// Returns a diagnostic which, if the position of the problem on the command line is
+    /// known, follows the message with the reconstructed command line and underlines the
+    /// offending arguments, e.g.
+    ///
+    /// ```text
+    /// parse: Unrecognized option: 'iter'
+    ///   program --iter=abc -v
+    ///           ^^^^^^^^^^
+    /// ```
+    ///
+    /// Otherwise this is the same plain text as `to_string()`. Each of multiple errors is
+    /// rendered on its own.
+    pub fn diagnostic(&self) -> String {
+        if !self.inner.errors.is_empty() {
+            let mut diagnostic = format!("{}{} errors occurred:", self.scope_prefix(), self.inner.errors.len());
+            for error in &self.inner.errors {
+                diagnostic.push_str(&format!("\n  {}", error.diagnostic().replace('\n', "\n  ")));
+            }
+            return diagnostic;
+        }
+
+        let (first, last) = match self.inner.span {
+            Some(span) => span,
+            None => { return self.to_string(); }
         };
+        let args = self.inner.command_line.iter().map(|arg| quote(arg)).collect::<Vec<String>>();
+        let start = args[..first].iter().map(|arg| help::display_width(arg) + 1).sum::<usize>();
+        let width = args[first..=last].iter().map(|arg| help::display_width(arg)).sum::<usize>() + last - first;
+        let mut diagnostic = format!("{}{}{}\n  {}\n  {}{}",
+            self.scope_prefix(), self.inner.msg, self.location_suffix(), args.join(" "), " ".repeat(start), "^".repeat(width.max(1)));
+        if let Some(ref usage) = self.inner.usage { diagnostic.push_str(&format!("\n\n{}", usage)); }
+        diagnostic
+    }
 
-        // Append the error message
-        desc.push_str(msg);
+    /// Returns an iterator over the individual errors, i.e. those listed by an error
+    /// of kind `Multiple` or else this error alone.
+    pub fn errors(&self) -> Iter<'_, ArgsError> {
+        if self.inner.errors.is_empty() { slice::from_ref(self).iter() } else { self.inner.errors.iter() }
+    }
 
-        // Append the usage message, if it exists
-        if !usage.to_string().is_empty() { desc.push_str(&format!("\n\n{}", usage)); }
+    /// Writes the `diagnostic` to standard error and exits the process with the `exit_code`.
+    pub fn exit(&self) -> ! {
+        let (code, stream, text) = self.report();
+        report::exit(code, stream, &text)
+    }
+
+    /// Returns the conventional exit code for this error, as in `sysexits.h`, i.e. `78`
+    /// (`EX_CONFIG`) for configuration problems and `64` (`EX_USAGE`) for any other.
+    pub fn exit_code(&self) -> i32 {
+        match self.inner.kind {
+            ErrorKind::Config => EX_CONFIG,
+            _ => EX_USAGE
+        }
+    }
+
+    /// Returns the kind of problem this error describes.
+    pub fn kind(&self) -> ErrorKind {
+        self.inner.kind
+    }
+
+    /// Returns the `file:line` of the response or configuration file the offending value was
+    /// read from, if any.
+    pub fn location(&self) -> Option<&str> {
+        self.inner.location.as_deref()
+    }
+
+    /// Returns the message of this error, without its scope or usage.
+    pub fn message(&self) -> &str {
+        &self.inner.msg
+    }
+
+    /// Returns the name of the option or positional this error concerns, if any.
+    pub fn opt_name(&self) -> Option<&str> {
+        self.inner.opt_name.as_deref()
+    }
+
+    /// Returns the exit code, stream and text which `exit` uses, without exiting.
+    pub fn report(&self) -> (i32, Stream, String) {
+        (self.exit_code(), Stream::Stderr, self.diagnostic())
+    }
+
+    /// Returns the scope of this error, e.g. `parse` or the option name.
+    pub fn scope(&self) -> &str {
+        &self.inner.scope
+    }
+
+    /// Returns the usage message attached to this error, if any.
+    pub fn usage(&self) -> Option<&str> {
+        self.inner.usage.as_deref()
+    }
+
+    /// Returns the index of the first offending argument on the command line, if known,
+    /// where index `0` is the program name as in `std::env::args()`.
+    pub fn position(&self) -> Option<usize> {
+        self.inner.span.map(|(first, _)| first)
+    }
 
-        ArgsError { desc: desc }
+    /// Returns the raw value which caused this error, if any.
+    pub fn value(&self) -> Option<&str> {
+        self.inner.value.as_deref()
+    }
+
+    /// Sets the kind of problem this error describes.
+    pub fn with_kind(mut self, kind: ErrorKind) -> ArgsError {
+        self.inner.kind = kind;
+        self
+    }
+
+    /// Sets the `file:line` of the response or configuration file the offending value was read from.
+    pub fn with_location(mut self, location: &str) -> ArgsError {
+        self.inner.location = Some(location.to_string());
+        self
+    }
+
+    /// Sets the name of the option or positional this error concerns.
+    pub fn with_opt_name(mut self, opt_name: &str) -> ArgsError {
+        self.inner.opt_name = Some(opt_name.to_string());
+        self
+    }
+
+    /// Sets the underlying error which caused this error, returned by `source()`.
+    pub fn with_source<E>(mut self, source: E) -> ArgsError where E: Into<Box<dyn Error + Send + Sync>> {
+        self.inner.source = Some(source.into());
+        self
+    }
+
+    /// Sets the `command_line`, starting with the program name, and the indices of the
+    /// `first` and `last` offending arguments within it.
+    pub fn with_span(mut self, command_line: &[String], first: usize, last: usize) -> ArgsError {
+        if first <= last && last < command_line.len() {
+            self.inner.command_line = command_line.to_vec();
+            self.inner.span = Some((first, last));
+        }
+        self
+    }
+
+    /// Sets the usage message attached to this error.
+    pub fn with_usage(mut self, usage: &str) -> ArgsError {
+        self.inner.usage = if usage.is_empty() { None } else { Some(usage.to_string()) };
+        self
+    }
+
+    /// Sets the raw value which caused this error.
+    pub fn with_value(mut self, value: &str) -> ArgsError {
+        self.inner.value = Some(value.to_string());
+        self
+    }
+
+    // Private instance methods
+    fn location_suffix(&self) -> String {
+        self.inner.location.as_ref().map_or_else(String::new, |location| format!(" (from {})", location))
+    }
+
+    fn scope_prefix(&self) -> String {
+        if self.inner.scope.is_empty() { String::new() } else { format!("{}: ", self.inner.scope) }
+    }
+}
+
+impl IntoIterator for ArgsError {
+    type Item = ArgsError;
+    type IntoIter = IntoIter<ArgsError>;
+
+    fn into_iter(mut self) -> IntoIter<ArgsError> {
+        if self.inner.errors.is_empty() { return vec!(self).into_iter(); }
+
+        mem::take(&mut self.inner.errors).into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ArgsError {
+    type Item = &'a ArgsError;
+    type IntoIter = Iter<'a, ArgsError>;
+
+    fn into_iter(self) -> Iter<'a, ArgsError> {
+        self.errors()
     }
 }
 
 impl Debug for ArgsError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.desc)
+        Display::fmt(self, f)
     }
 }
 
 impl Display for ArgsError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}", self.desc)
+        // If there is a scope, append it to the front
+        write!(f, "{}", self.scope_prefix())?;
+
+        // Append the error message and where it was read from, if it was in a response file
+        write!(f, "{}{}", self.inner.msg, self.location_suffix())?;
+
+        // Append the usage message, if it exists
+        if let Some(ref usage) = self.inner.usage { write!(f, "\n\n{}", usage)?; }
+        Ok(())
     }
 }
 
 impl Error for ArgsError {
-    fn description(&self) -> &str {
-        &self.desc
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.inner.source {
+            Some(ref source) => Some(&**source),
+            None => None
+        }
     }
 }
 
+// Private associated methods
+fn quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.chars().any(|c| c.is_whitespace() || c == '\'' || c == '"') {
+        return arg.to_string();
+    }
+
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
@@ -0,0 +1,81 @@
+use super::{ArgsError,ErrorKind,SCOPE_PARSE};
+
+#[cfg(test)] mod tst;
+
+/// How many members of a `Group`, relative to one, may be given.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Multiplicity {
+    /// At least one member must be given.
+    AtLeast,
+    /// No more than one member may be given.
+    AtMost,
+    /// Exactly one member must be given.
+    Exactly
+}
+
+/// A named set of options which is enforced after parsing.
+pub struct Group {
+    name: String,
+    members: Vec<String>,
+    multiplicity: Multiplicity
+}
+
+impl Group {
+    /// Creates a new `Group` with the provided `name`, `members` and `multiplicity`.
+    pub fn new(name: &str, members: &[&str], multiplicity: Multiplicity) -> Group {
+        Group {
+            name: name.to_string(),
+            members: members.iter().map(|member| member.to_string()).collect(),
+            multiplicity
+        }
+    }
+
+    /// Checks the group against the options which were `given`.
+    pub fn check<G>(&self, given: G) -> Result<(), ArgsError> where G: Fn(&str) -> bool {
+        let count = self.members.iter().filter(|member| given(member)).count();
+        let (kind, msg) = match self.multiplicity {
+            Multiplicity::AtLeast | Multiplicity::Exactly if count == 0 => {
+                (ErrorKind::MissingRequired,
+                    format!("Group '{}' requires one of {}", self.name, self.quoted_members()))
+            },
+            Multiplicity::AtMost | Multiplicity::Exactly if count > 1 => {
+                (ErrorKind::Conflict,
+                    format!("Group '{}' allows only one of {}", self.name, self.quoted_members()))
+            },
+            _ => { return Ok(()); }
+        };
+
+        Err(ArgsError::new(SCOPE_PARSE, &msg).with_kind(kind))
+    }
+
+    /// Returns the names of the options in this group.
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    /// Returns the name of this group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Generates a short usage fragment from the `fragment` of each member.
+    pub fn short_usage<F>(&self, fragment: F) -> String where F: Fn(&str) -> String {
+        let fragments = self.members.iter()
+            .map(|member| fragment(member))
+            .collect::<Vec<String>>()
+            .join(" | ");
+
+        match self.multiplicity {
+            Multiplicity::AtMost => format!("[{}]", fragments),
+            _ => format!("({})", fragments)
+        }
+    }
+
+    // Private instance methods
+    fn quoted_members(&self) -> String {
+        self.members.iter()
+            .map(|member| format!("'{}'", member))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
@@ -0,0 +1,113 @@
+use groups::{Group,Multiplicity};
+
+fn create(multiplicity: Multiplicity) -> Group {
+    Group::new("source", &["file", "url", "stdin"], multiplicity)
+}
+
+mod check {
+    mod at_least_one {
+        mod none_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_err() {
+                let error = create(Multiplicity::AtLeast).check(|_| false).unwrap_err();
+
+                assert_eq!("parse: Group 'source' requires one of 'file', 'url', 'stdin'", error.to_string());
+            }
+        }
+
+        mod many_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_ok() {
+                assert!(create(Multiplicity::AtLeast).check(|_| true).is_ok());
+            }
+        }
+    }
+
+    mod at_most_one {
+        mod none_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_ok() {
+                assert!(create(Multiplicity::AtMost).check(|_| false).is_ok());
+            }
+        }
+
+        mod many_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_err() {
+                let error = create(Multiplicity::AtMost).check(|_| true).unwrap_err();
+
+                assert_eq!("parse: Group 'source' allows only one of 'file', 'url', 'stdin'", error.to_string());
+            }
+        }
+    }
+
+    mod exactly_one {
+        mod none_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_err() {
+                assert!(create(Multiplicity::Exactly).check(|_| false).is_err());
+            }
+        }
+
+        mod one_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_ok() {
+                assert!(create(Multiplicity::Exactly).check(|member| member == "url").is_ok());
+            }
+        }
+
+        mod many_given {
+            use groups::Multiplicity;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_err() {
+                assert!(create(Multiplicity::Exactly).check(|_| true).is_err());
+            }
+        }
+    }
+}
+
+mod short_usage {
+    mod exactly_one {
+        use groups::Multiplicity;
+        use super::super::create;
+
+        #[test]
+        fn returns_parenthesized() {
+            let usage = create(Multiplicity::Exactly).short_usage(|member| format!("--{}", member));
+
+            assert_eq!("(--file | --url | --stdin)", usage);
+        }
+    }
+
+    mod at_most_one {
+        use groups::Multiplicity;
+        use super::super::create;
+
+        #[test]
+        fn returns_bracketed() {
+            let usage = create(Multiplicity::AtMost).short_usage(|member| format!("--{}", member));
+
+            assert_eq!("[--file | --url | --stdin]", usage);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use unicode_width::{UnicodeWidthChar,UnicodeWidthStr};
+
+#[cfg(test)] mod tst;
+
+/// The number of columns rows are indented by.
+const INDENT: usize = 4;
+/// The narrowest descriptions are wrapped to, regardless of the available width.
+const MIN_DESC_WIDTH: usize = 20;
+
+/// Renders a section headed by `title` with one row per term and description. Descriptions
+/// start in the column after `term_width` and are wrapped to fit within `width` columns,
+/// continuing with a hanging indent. Terms too wide for their column get a line of their own.
+pub fn section(title: &str, rows: &[(String, String)], term_width: usize, width: usize) -> String {
+    let desc_column = INDENT + term_width;
+    let desc_width = width.saturating_sub(desc_column).max(MIN_DESC_WIDTH);
+    let hanging_indent = format!("\n{}", " ".repeat(desc_column));
+
+    let mut section = format!("\n{}:\n", title);
+    for (term, desc) in rows {
+        let mut row = format!("{}{}", " ".repeat(INDENT), term);
+        if !desc.is_empty() {
+            let term_len = display_width(term);
+            if term_len < term_width {
+                row.push_str(&" ".repeat(term_width - term_len));
+            } else {
+                row.push_str(&hanging_indent);
+            }
+            row.push_str(&wrap(desc, desc_width).join(&hanging_indent));
+        }
+        section.push_str(&row);
+        section.push('\n');
+    }
+    section
+}
+
+/// Returns the number of columns `text` occupies in a terminal.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Splits `text` into lines no wider than `width` columns, breaking between words where
+/// possible and within words which are wider than `width` on their own.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let line_len = display_width(&line);
+            let word_len = display_width(word);
+            if line.is_empty() || line_len + 1 + word_len > width {
+                if !line.is_empty() { lines.push(line); }
+                line = String::new();
+                let mut line_len = 0;
+
+                // Break words which do not fit on a line of their own
+                for c in word.chars() {
+                    let c_len = c.width().unwrap_or(0);
+                    if line_len + c_len > width && !line.is_empty() {
+                        lines.push(line);
+                        line = String::new();
+                        line_len = 0;
+                    }
+                    line.push(c);
+                    line_len += c_len;
+                }
+            } else {
+                line.push(' ');
+                line.push_str(word);
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
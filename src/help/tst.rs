@@ -0,0 +1,72 @@
+mod section {
+    mod short_term {
+        use help;
+
+        #[test]
+        fn aligns_description() {
+            let rows = vec!(("-f, --flag".to_string(), "Flag".to_string()));
+
+            assert_eq!("\nOptions:\n    -f, --flag          Flag\n", help::section("Options", &rows, 20, 80));
+        }
+    }
+
+    mod long_term {
+        use help;
+
+        #[test]
+        fn moves_description_to_next_line() {
+            let rows = vec!(("-o, --output-file FILE".to_string(), "Output".to_string()));
+
+            assert_eq!("\nOptions:\n    -o, --output-file FILE\n                        Output\n",
+                help::section("Options", &rows, 20, 80));
+        }
+    }
+
+    mod long_description {
+        use help;
+
+        #[test]
+        fn wraps_with_hanging_indent() {
+            let rows = vec!(("-f".to_string(), "one two three four five six".to_string()));
+
+            assert_eq!("\nOptions:\n    -f                  one two three four\n                        five six\n",
+                help::section("Options", &rows, 20, 44));
+        }
+    }
+
+    mod wide_characters {
+        use help;
+
+        #[test]
+        fn aligns_by_display_width() {
+            let rows = vec!(("数据".to_string(), "Data".to_string()), ("ab".to_string(), "Other".to_string()));
+
+            assert_eq!("\nArguments:\n    数据                Data\n    ab                  Other\n",
+                help::section("Arguments", &rows, 20, 80));
+        }
+    }
+}
+
+mod wrap {
+    use help;
+
+    #[test]
+    fn breaks_between_words() {
+        assert_eq!(vec!("the quick", "brown fox"), help::wrap("the quick brown fox", 10));
+    }
+
+    #[test]
+    fn breaks_long_words() {
+        assert_eq!(vec!("abcd", "efgh", "ij"), help::wrap("abcdefghij", 4));
+    }
+
+    #[test]
+    fn keeps_line_breaks() {
+        assert_eq!(vec!("first", "second"), help::wrap("first\nsecond", 80));
+    }
+
+    #[test]
+    fn measures_display_width() {
+        assert_eq!(vec!("日本", "語"), help::wrap("日本語", 4));
+    }
+}
@@ -4,7 +4,8 @@
 //!
 //! In order to use the `args` crate simply create an `Args` object and begin
 //! registering possible command line options via the `flag(...)` and `option(...)`
-//! methods. Once all options have been registered, parse arguments directly from the
+//! methods, along with any positional arguments via `positional(...)`. Once all
+//! options have been registered, parse arguments directly from the
 //! command line, or provide a vector of your own arguments.
 //!
 //! Any errors encountered during parsing will be returned wrapped in an `ArgsError`.
@@ -20,7 +21,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! args = "2.0"
+//! args = "3.0"
 //! ```
 //!
 //! and this to your crate root:
@@ -29,6 +30,17 @@
 //! extern crate args;
 //! ```
 //!
+//! # Upgrading from 2.x
+//!
+//! Version 3.0 changes the public API in a few places:
+//!
+//! * `parse(...)` and `parse_from_cli()`, including those of `HasArgs`, return a
+//!   `ParseOutcome` which is `Parsed` unless help or the version was requested.
+//! * `iter()` visits each option along with all of its values, i.e. `Vec<String>`.
+//! * `value_of(...)` and the other typed accessors, including those of `HasParsedArgs`,
+//!   require the `FromStr` error to convert into `Box<dyn Error + Send + Sync>`, which
+//!   excludes types whose error is `()`.
+//!
 //! # Example
 //!
 //! The following example shows simple command line parsing for an application that
@@ -40,27 +52,21 @@
 //! extern crate getopts;
 //!
 //! use getopts::Occur;
-//! use std::process::exit;
 //!
 //! use args::{Args,ArgsError};
 //! use args::validations::{Order,OrderValidation};
 //!
-//! const PROGRAM_DESC: &'static str = "Run this program";
-//! const PROGRAM_NAME: &'static str = "program";
+//! const PROGRAM_DESC: &str = "Run this program";
+//! const PROGRAM_NAME: &str = "program";
 //!
 //! fn main() {
-//!     match parse(&vec!("-i", "5")) {
-//!         Ok(_) => println!("Successfully parsed args"),
-//!         Err(error) => {
-//!             println!("{}", error);
-//!             exit(1);
-//!         }
-//!     };
+//!     // Print the error to stderr and exit with a usage error code
+//!     if let Err(error) = parse(&vec!("-i", "5")) { error.exit(); }
 //! }
 //!
 //! fn parse(input: &Vec<&str>) -> Result<(), ArgsError> {
 //!     let mut args = Args::new(PROGRAM_NAME, PROGRAM_DESC);
-//!     args.flag("h", "help", "Print the usage menu");
+//!     args.help_flag("h", "help", "Print the usage menu");
 //!     args.option("i",
 //!         "iter",
 //!         "The number of times to run this program",
@@ -74,13 +80,9 @@
 //!         Occur::Optional,
 //!         Some(String::from("output.log")));
 //!
-//!     try!(args.parse(input));
-//!
-//!     let help = try!(args.value_of("help"));
-//!     if help {
-//!         args.full_usage();
-//!         return Ok(());
-//!     }
+//!     // Print the usage to stdout and exit successfully if help was requested
+//!     let outcome = try!(args.parse(input));
+//!     if !outcome.is_parsed() { outcome.exit(); }
 //!
 //!     let gt_0 = Box::new(OrderValidation::new(Order::GreaterThan, 0u32));
 //!     let lt_10 = Box::new(OrderValidation::new(Order::LessThanOrEqual, 10u32));
@@ -104,40 +106,120 @@
 
 #[macro_use] extern crate log;
 extern crate getopts;
+extern crate unicode_width;
 
-use getopts::{Fail,HasArg,Occur,Options};
-use std::collections::BTreeMap;
+use getopts::{Fail,HasArg,Occur,Options,ParsingStyle};
+use std::collections::{BTreeMap,BTreeSet};
 use std::collections::btree_map::Iter;
 use std::env;
-use std::ffi::OsStr;
+use std::error::Error;
+use std::ffi::{OsStr,OsString};
 use std::fmt::{self,Display,Formatter};
 use std::iter::IntoIterator;
+use std::mem;
+use std::path::{Path,PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 
-pub use self::errors::ArgsError;
+pub use self::completions::Shell;
+pub use self::errors::{ArgsError,ErrorKind};
+pub use self::maybe::MaybeValue;
+pub use self::outcome::ParseOutcome;
+pub use self::report::{Stream,EX_CONFIG,EX_OK,EX_USAGE};
 
-use self::options::Opt;
+use self::aliases::Alias;
+use self::groups::{Group,Multiplicity};
+use self::options::{Opt,NEGATION_PREFIX};
+use self::positionals::Positional;
+use self::rules::Rule;
 use self::validations::Validation;
 
 pub mod traits;
 pub mod validations;
 
+mod aliases;
+mod completions;
+mod config;
 mod errors;
+mod groups;
+mod help;
+mod maybe;
 mod options;
+mod outcome;
+mod positionals;
+mod recovery;
+mod reference;
+mod report;
+mod response;
+mod rules;
+mod suggestions;
 #[cfg(test)] mod tst;
 
 const COLUMN_WIDTH: usize = 20;
-const SCOPE_PARSE: &'static str = "parse";
-const SEPARATOR: &'static str = ",";
+const COLUMNS_VAR: &str = "COLUMNS";
+const DEFAULT_MAX_WIDTH: usize = 80;
+const DEFAULT_HEADING: &str = "Options";
+const HELP_COMMAND: &str = "help";
+const SCOPE_PARSE: &str = "parse";
+const USAGE_COLUMN: usize = 20;
+
+type EnvLookup = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Why an option is deprecated and which option, if any, replaces it.
+struct Deprecation {
+    message: String,
+    replacement: Option<String>
+}
+
+/// Where the values of an option came from, in order of precedence. Values from a
+/// configuration file keep the `file:line` of their entry.
+#[derive(Clone,Debug,PartialEq)]
+enum Source {
+    Cli,
+    Env,
+    Config(String),
+    Default
+}
 
 /// A dead simple implementation of command line argument parsing and validation.
 pub struct Args {
+    after_help: Option<String>,
+    aliases: Vec<Alias>,
+    args: Vec<String>,
+    before_help: Option<String>,
+    collect_errors: bool,
+    command_line: Vec<String>,
+    config_entries: Vec<config::Entry>,
+    config_file: Option<PathBuf>,
+    config_option: Option<String>,
+    deprecations: BTreeMap<String, Deprecation>,
     description: String,
-    options: Options,
-    opts: BTreeMap<String, Box<Opt>>,
+    env_lookup: EnvLookup,
+    envs: BTreeMap<String, String>,
+    groups: Vec<Group>,
+    heading: Option<String>,
+    help_flag: Option<String>,
+    headings: BTreeMap<String, String>,
+    locations: Vec<Option<String>>,
+    man_sections: Vec<(String, String)>,
+    max_width: usize,
+    opts: BTreeMap<String, Box<dyn Opt>>,
     opt_names: Vec<String>,
+    positionals: Vec<Positional>,
+    possible_values: BTreeMap<String, Vec<String>>,
+    positional_values: BTreeMap<String, Vec<String>>,
+    positions: Vec<usize>,
     program_name: String,
-    values: BTreeMap<String, String>
+    response_files: bool,
+    rules: Vec<Rule>,
+    sources: BTreeMap<String, Source>,
+    subcommand: Option<String>,
+    subcommand_names: Vec<String>,
+    subcommands: BTreeMap<String, Args>,
+    valueless: BTreeSet<String>,
+    values: BTreeMap<String, Vec<String>>,
+    version: Option<(String, String)>,
+    warnings: Vec<String>
 }
 
 impl Args {
@@ -147,16 +229,252 @@ impl Args {
         debug!("Creating new args object for '{}'", program_name);
 
         Args {
+            after_help: None,
+            aliases: Vec::new(),
+            args: Vec::new(),
+            before_help: None,
+            collect_errors: false,
+            command_line: Vec::new(),
+            config_entries: Vec::new(),
+            config_file: None,
+            config_option: None,
+            deprecations: BTreeMap::new(),
             description: description.to_string(),
-            options: Options::new(),
+            env_lookup: Arc::new(|name| env::var(name).ok()),
+            envs: BTreeMap::new(),
+            groups: Vec::new(),
+            heading: None,
+            help_flag: None,
+            headings: BTreeMap::new(),
+            locations: Vec::new(),
+            man_sections: Vec::new(),
+            max_width: DEFAULT_MAX_WIDTH,
             opts: BTreeMap::new(),
             opt_names: Vec::new(),
+            positionals: Vec::new(),
+            possible_values: BTreeMap::new(),
+            positional_values: BTreeMap::new(),
+            positions: Vec::new(),
             program_name: program_name.to_string(),
-            values: BTreeMap::new()
+            response_files: false,
+            rules: Vec::new(),
+            sources: BTreeMap::new(),
+            subcommand: None,
+            subcommand_names: Vec::new(),
+            subcommands: BTreeMap::new(),
+            valueless: BTreeSet::new(),
+            values: BTreeMap::new(),
+            version: None,
+            warnings: Vec::new()
         }
     }
 
     // Public instance methods
+    /// Sets free-form text, e.g. examples or exit codes, shown at the end of `usage`.
+    pub fn after_help(&mut self, text: &str) -> &mut Args {
+        self.after_help = Some(text.trim_end().to_string());
+
+        self
+    }
+
+    /// Registers `alias` as an alternative name for the `Opt` identified by `opt_name`,
+    /// e.g. to keep a renamed option working. A single character `alias` is a short name.
+    /// The alias is not shown in the usage message; see `visible_alias`.
+    pub fn alias(&mut self, opt_name: &str, alias: &str) -> &mut Args {
+        self.register_alias(opt_name, alias, false);
+
+        self
+    }
+
+    /// Declares a group named `name` of which at least one of the `members` must be given.
+    pub fn at_least_one(&mut self, name: &str, members: &[&str]) -> &mut Args {
+        self.register_group(Group::new(name, members, Multiplicity::AtLeast));
+
+        self
+    }
+
+    /// Declares a group named `name` of which no more than one of the `members` may be given.
+    pub fn at_most_one(&mut self, name: &str, members: &[&str]) -> &mut Args {
+        self.register_group(Group::new(name, members, Multiplicity::AtMost));
+
+        self
+    }
+
+    /// Sets free-form text shown in `usage` between the description and the options.
+    pub fn before_help(&mut self, text: &str) -> &mut Args {
+        self.before_help = Some(text.trim_end().to_string());
+
+        self
+    }
+
+    /// Enables or disables collecting errors. When enabled, `parse` carries on past
+    /// unrecognized options, missing required options, invalid values and violated
+    /// groups or rules, and fails with a single error of kind `ErrorKind::Multiple`
+    /// listing all of them. Likewise `validated_value_of` reports every failing
+    /// `Validation` rather than only the first. Subcommands inherit this setting.
+    pub fn collect_errors(&mut self, enabled: bool) -> &mut Args {
+        self.collect_errors = enabled;
+
+        self
+    }
+
+    /// Generates a completion script for `shell` covering the registered options,
+    /// their possible values and any subcommands.
+    pub fn completions(&self, shell: Shell) -> String {
+        completions::generate(&self.completion_command(), shell)
+    }
+
+    /// Sets the path of a configuration file whose values are used for options that
+    /// are not given on the command line or in the environment. Keys correspond to
+    /// option long names, and keys under a `[section]` apply to the subcommand of the
+    /// same name. The file is ignored if it does not exist.
+    pub fn config_file<P: AsRef<Path>>(&mut self, path: P) -> &mut Args {
+        self.config_file = Some(path.as_ref().to_path_buf());
+
+        self
+    }
+
+    /// Designates the `Opt` identified by `opt_name`, e.g. `"config"`, as holding the
+    /// path of the configuration file. A path given on the command line or in the
+    /// environment must exist and takes precedence over one set via `config_file(...)`.
+    pub fn config_option(&mut self, opt_name: &str) -> &mut Args {
+        if self.opts.contains_key(opt_name) {
+            self.config_option = Some(opt_name.to_string());
+        } else {
+            warn!("{} is not registered, ignoring", opt_name);
+        }
+
+        self
+    }
+
+    /// Declares that the `Opt` identified by `opt_name` cannot be given along with
+    /// the `Opt` identified by `other`, e.g. `--input` and `--stdin`.
+    pub fn conflicts_with(&mut self, opt_name: &str, other: &str) -> &mut Args {
+        self.register_rule(Rule::ConflictsWith(opt_name.to_string(), other.to_string()));
+
+        self
+    }
+
+    /// Registers a flag which may be repeated, e.g. `-vvv`, and whose value is the number
+    /// of times it was given. Retrieve it as an integer, e.g. `value_of::<u8>("verbose")`.
+    /// The count is raised to `min` even if the flag is not given at all, which makes
+    /// `min` a floor, e.g. a default verbosity, rather than a requirement.
+    ///
+    /// * `short_name` - e.g. `"v"` for a `-v` option, or `""` for none
+    /// * `long_name` - e.g. `"verbose"` for a `--verbose` option, or `""` for none
+    /// * `desc` - A description of the flag for the usage message
+    /// * `min` - The lowest count reported, even if the flag is given fewer times or not at all
+    /// * `max` - The highest count reported, regardless of how often the flag is given
+    pub fn counted_flag(&mut self,
+            short_name: &str,
+            long_name: &str,
+            desc: &str,
+            min: Option<usize>,
+            max: Option<usize>) -> &mut Args {
+        self.register_opt(
+            options::count(short_name,
+                long_name,
+                desc,
+                min,
+                max
+            )
+        );
+
+        self
+    }
+
+    /// Registers an option which may be given multiple times and whose values are
+    /// additionally split on `delimiter`, e.g. `--tags a,b --tags c` yields `a`, `b` and `c`.
+    ///
+    /// * `short_name` - e.g. `"t"` for a `-t` option, or `""` for none
+    /// * `long_name` - e.g. `"tags"` for a `--tags` option, or `""` for none
+    /// * `desc` - A description of the option for the usage message
+    /// * `hint` - A hint to be used in place of the argument in the usage message,
+    ///   e.g. `"TAGS"` for a `-t TAGS` option
+    /// * `delimiter` - The string on which each value is split, e.g. `","`
+    pub fn delimited_option(&mut self,
+            short_name: &str,
+            long_name: &str,
+            desc: &str,
+            hint: &str,
+            delimiter: &str) -> &mut Args {
+        self.register_opt(
+            options::delimited(short_name,
+                long_name,
+                desc,
+                hint,
+                delimiter
+            )
+        );
+
+        self
+    }
+
+    /// Marks the `Opt` identified by `opt_name` as deprecated with the provided `message`.
+    /// Giving a deprecated option records a warning (see `warnings`) and, if a `replacement`
+    /// is provided, its values are forwarded to the replacement unless that was given too.
+    /// Deprecated options are annotated in `usage` and left out of `short_usage`.
+    pub fn deprecated(&mut self, opt_name: &str, message: &str, replacement: Option<&str>) -> &mut Args {
+        if replacement.is_some_and(|replacement| !self.opts.contains_key(replacement)) {
+            warn!("Replacement for '{}' is not registered, ignoring", opt_name);
+            return self;
+        }
+
+        match self.opts.get_mut(opt_name) {
+            Some(opt) => {
+                opt.annotate(&match replacement {
+                    Some(replacement) => format!("[deprecated: use --{}]", replacement),
+                    None => "[deprecated]".to_string()
+                });
+                self.deprecations.insert(opt_name.to_string(), Deprecation {
+                    message: message.to_string(),
+                    replacement: replacement.map(|replacement| replacement.to_string())
+                });
+            },
+            None => { warn!("Option '{}' is not registered, ignoring deprecation", opt_name); }
+        }
+
+        self
+    }
+
+    /// Declares an environment variable from which the `Opt` identified by `opt_name`
+    /// takes its value when it is not given on the command line. Values given on the
+    /// command line take precedence over the environment, which takes precedence over
+    /// the default. Flags accept truthy values (e.g. `1`, `true`, `yes`, `on`) and
+    /// multi options split the variable on their delimiter, or `,` if there is none.
+    ///
+    /// * `opt_name` - The name of a registered option, e.g. `"port"`
+    /// * `var` - The name of the environment variable, e.g. `"MYAPP_PORT"`
+    pub fn env(&mut self, opt_name: &str, var: &str) -> &mut Args {
+        match self.opts.get_mut(opt_name) {
+            Some(opt) => {
+                opt.annotate(&format!("[env: {}]", var));
+                self.envs.insert(opt_name.to_string(), var.to_string());
+            },
+            None => { warn!("{} is not registered, ignoring", opt_name); }
+        }
+
+        self
+    }
+
+    /// Replaces the function used to look up environment variables, which defaults
+    /// to reading the process environment. Subcommands inherit this lookup.
+    pub fn env_lookup<F>(&mut self, lookup: F) -> &mut Args
+            where F: Fn(&str) -> Option<String> + Send + Sync + 'static {
+        self.env_lookup = Arc::new(lookup);
+
+        self
+    }
+
+    /// Declares a group named `name` of which exactly one of the `members` must be given,
+    /// e.g. one of `--file`, `--url` or `--stdin`. An option may belong to several groups,
+    /// each of which is checked on its own.
+    pub fn exactly_one(&mut self, name: &str, members: &[&str]) -> &mut Args {
+        self.register_group(Group::new(name, members, Multiplicity::Exactly));
+
+        self
+    }
+
     /// Registers an optional flag argument that does not take an argument and defaults to false.
     ///
     /// * `short_name` - e.g. `"h"` for a `-h` option, or `""` for none
@@ -190,23 +508,162 @@ impl Args {
         !self.opts.is_empty()
     }
 
+    /// Returns a `bool` indicating whether or not any positional arguments are registered.
+    pub fn has_positionals(&self) -> bool {
+        !self.positionals.is_empty()
+    }
+
+    /// Returns a `bool` indicating whether or not any subcommands are registered.
+    pub fn has_subcommands(&self) -> bool {
+        !self.subcommands.is_empty()
+    }
+
     /// Returns a `bool` indicating whether or not a argument is present.
     pub fn has_value(&self, opt_name: &str) -> bool {
-        self.values.get(opt_name).is_some()
+        self.values.contains_key(opt_name)
+    }
+
+    /// Lists any options registered after this call under `heading` in `usage`, e.g.
+    /// "Input" or "Networking". An empty `heading` returns to the default "Options".
+    pub fn heading(&mut self, heading: &str) -> &mut Args {
+        self.heading = if heading.is_empty() { None } else { Some(heading.to_string()) };
+
+        self
+    }
+
+    /// Registers a flag which, when given, makes `parse` return `ParseOutcome::Help` with
+    /// the full usage message instead of checking for required options and the like.
+    /// The flag is ignored unless it has a `long_name`.
+    pub fn help_flag(&mut self, short_name: &str, long_name: &str, desc: &str) -> &mut Args {
+        if long_name.is_empty() {
+            warn!("Help flag '{}' has no long name, ignoring", short_name);
+            return self;
+        }
+
+        self.flag(short_name, long_name, desc);
+        self.help_flag = Some(long_name.to_string());
+
+        self
+    }
+
+    /// Renders a standalone HTML reference of the registered options, positionals and
+    /// subcommands, with stable anchors such as `#option-iter`.
+    pub fn html_reference(&self) -> String {
+        reference::html::render(&self.reference_page())
     }
 
     /// Returns an iterator visiting all key-value pairs in alphabetical order.
-    pub fn iter(&self) -> Iter<String, String> {
+    /// Each option maps to its values in the order in which they were given.
+    pub fn iter(&self) -> Iter<'_, String, Vec<String>> {
         self.values.iter()
     }
 
+    /// Renders a roff man page from the registered options, positionals, subcommands
+    /// and any sections added via `man_section`.
+    pub fn man_page(&self) -> String {
+        reference::man::render(&self.reference_page())
+    }
+
+    /// Adds a section, e.g. `EXAMPLES`, `ENVIRONMENT` or `SEE ALSO`, to the end of the man page.
+    /// Blank lines in `body` separate paragraphs.
+    pub fn man_section(&mut self, title: &str, body: &str) -> &mut Args {
+        self.man_sections.push((title.to_string(), body.to_string()));
+
+        self
+    }
+
+    /// Renders a Markdown reference of the registered options, positionals and
+    /// subcommands, with stable anchors such as `#option-iter`.
+    pub fn markdown_reference(&self) -> String {
+        reference::markdown::render(&self.reference_page())
+    }
+
+    /// Sets the maximum width `usage` is wrapped to, which defaults to 80 columns.
+    /// If the `COLUMNS` environment variable is set to a narrower width it is used
+    /// instead; it is read through `env_lookup` so it can be overridden.
+    pub fn max_width(&mut self, width: usize) -> &mut Args {
+        self.max_width = width;
+
+        self
+    }
+
+    /// Registers an optional option whose argument may be omitted, e.g. `--color` or
+    /// `--color=never`. Use `maybe_value_of(...)` to distinguish between the two.
+    ///
+    /// * `short_name` - e.g. `"c"` for a `-c` option, or `""` for none
+    /// * `long_name` - e.g. `"color"` for a `--color` option, or `""` for none
+    /// * `desc` - A description of the option for the usage message
+    /// * `hint` - A hint to be used in place of the argument in the usage message,
+    ///   e.g. `"WHEN"` for a `--color [WHEN]` option
+    /// * `implicit` - The value to use when the option is given without an argument
+    pub fn maybe_option(&mut self,
+            short_name: &str,
+            long_name: &str,
+            desc: &str,
+            hint: &str,
+            implicit: Option<String>) -> &mut Args {
+        self.register_opt(
+            options::maybe(short_name,
+                long_name,
+                desc,
+                hint,
+                Occur::Optional,
+                None,
+                implicit
+            )
+        );
+
+        self
+    }
+
+    /// Retrieves the state of the `Opt` identified by `opt_name`, casting any value
+    /// to the type specified by `T`. This distinguishes an option that was absent,
+    /// given without a value, or given with a value.
+    ///
+    /// # Failures
+    ///
+    /// Returns `Err(ArgsError)` if the value cannot be cast to type `T`.
+    pub fn maybe_value_of<T: FromStr>(&self, opt_name: &str) -> Result<MaybeValue<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        if self.valueless.contains(opt_name) {
+            Ok(MaybeValue::Present(self.optional_value_of::<T>(opt_name)?))
+        } else if self.has_value(opt_name) {
+            Ok(MaybeValue::Value(self.value_of::<T>(opt_name)?))
+        } else {
+            Ok(MaybeValue::Absent)
+        }
+    }
+
+    /// Registers a boolean flag which may also be given as `--no-<long_name>`, e.g.
+    /// `--color` and `--no-color`. If both forms are given the last one wins.
+    ///
+    /// * `short_name` - e.g. `"c"` for a `-c` option, or `""` for none
+    /// * `long_name` - e.g. `"color"` for `--color` and `--no-color` options
+    /// * `desc` - A description of the flag for the usage message
+    /// * `default` - The value of the flag when neither form is given
+    pub fn negatable_flag(&mut self,
+            short_name: &str,
+            long_name: &str,
+            desc: &str,
+            default: bool) -> &mut Args {
+        self.register_opt(
+            options::negatable(short_name,
+                long_name,
+                desc,
+                default
+            )
+        );
+
+        self
+    }
+
     /// Registers an option explicitly.
     ///
     /// * `short_name` - e.g. `"h"` for a `-h` option, or `""` for none
     /// * `long_name` - e.g. `"help"` for a `--help` option, or `""` for none
     /// * `desc` - A description of the flag for the usage message
     /// * `hint` - A hint to be used in place of the argument in the usage message,
-    /// e.g. `"FILE"` for a `-o FILE` option
+    ///   e.g. `"FILE"` for a `-o FILE` option
     /// * `occur` - An enum representing whether the option is required or not
     /// * `default` - The default value for this option if there should be one
     pub fn option(&mut self,
@@ -234,42 +691,20 @@ impl Args {
     ///
     /// # Failures
     /// Fails if any errors are encountered during parsing.
-    pub fn parse<C: IntoIterator>(&mut self, raw_args: C) -> Result<(), ArgsError> where C::Item: AsRef<OsStr> {
-        debug!("Parsing args for '{}'", self.program_name);
-
-        // Get matches and return an error if there is a problem parsing
-        let matches = match self.options.parse(raw_args) {
-            Ok(matches) => { matches },
-            Err(error) => { return Err(ArgsError::new(SCOPE_PARSE, &error.to_string())) }
-        };
-
-        // Find matches and store the values (or a default)
-        for opt_name in &self.opt_names {
-            let option = self.opts.get(opt_name);
-            if option.is_none() {
-                return Err(ArgsError::new(SCOPE_PARSE, &Fail::UnrecognizedOption(opt_name.to_string()).to_string()));
-            }
-
-            let opt = option.unwrap();
-            let value = opt.parse(&matches).unwrap_or("".to_string());
-            if !value.is_empty() {
-                self.values.insert(opt_name.to_string(), value);
-            } else {
-                if opt.is_required() {
-                    return Err(ArgsError::new(SCOPE_PARSE, &Fail::ArgumentMissing(opt_name.to_string()).to_string()));
-                }
-            }
-        }
-
-        debug!("Args: {:?}", self.values);
-        Ok(())
+    pub fn parse<C: IntoIterator>(&mut self, raw_args: C) -> Result<ParseOutcome, ArgsError> where C::Item: AsRef<OsStr> {
+        let raw_args = raw_args.into_iter().map(|arg| arg.as_ref().to_os_string()).collect::<Vec<OsString>>();
+        let mut command_line = self.program_name.split_whitespace().map(|name| name.to_string()).collect::<Vec<String>>();
+        let positions = (command_line.len()..command_line.len() + raw_args.len()).collect();
+        let locations = vec!(None; raw_args.len());
+        command_line.extend(raw_args.iter().map(|arg| arg.to_string_lossy().into_owned()));
+        self.parse_args(raw_args, command_line, positions, locations)
     }
 
     /// Parses arguments directly from the command line according to the registered options.
     ///
     /// # Failures
     /// Fails if any errors are encountered during parsing.
-    pub fn parse_from_cli(&mut self) -> Result<(), ArgsError> {
+    pub fn parse_from_cli(&mut self) -> Result<ParseOutcome, ArgsError> {
         // Retrieve the cli args and throw out the program name
         let mut raw_args: Vec<String> = env::args().collect();
         if !raw_args.is_empty() { raw_args.remove(0); }
@@ -277,15 +712,227 @@ impl Args {
         self.parse(&mut raw_args)
     }
 
+    /// Registers a positional argument, i.e. a free argument following the options.
+    /// Positionals are matched in the order in which they are registered.
+    ///
+    /// * `name` - The name used to retrieve the value, e.g. `"file"`
+    /// * `desc` - A description of the argument for the usage message
+    /// * `hint` - A hint to be used in place of the argument in the usage message,
+    ///   e.g. `"FILE"`, or `""` to use the upper-cased name
+    /// * `occur` - Whether the argument is required (`Occur::Req`), optional
+    ///   (`Occur::Optional`) or consumes all remaining arguments (`Occur::Multi`)
+    pub fn positional(&mut self,
+            name: &str,
+            desc: &str,
+            hint: &str,
+            occur: Occur) -> &mut Args {
+        self.register_positional(Positional::new(name, desc, hint, occur));
+
+        self
+    }
+
+    /// Retrieves the value of the positional identified by `name` and casts it to
+    /// the type specified by `T`.
+    ///
+    /// # Failures
+    ///
+    /// Returns `Err(ArgsError)` if the positional has no value or if the
+    /// value cannot be cast to type `T`.
+    pub fn positional_of<T: FromStr>(&self, name: &str) -> Result<T, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.positional_values.get(name).and_then(|values| values.first())
+            .ok_or_else(|| no_value(name))
+            .and_then(|value| parse_value(name, value))
+            .map_err(|error| self.locate(error))
+    }
+
+    /// Retrieves all values of the positional identified by `name` and casts
+    /// each of them to the type specified by `T`. A positional that was not
+    /// given yields an empty vector.
+    ///
+    /// # Failures
+    ///
+    /// Returns `Err(ArgsError)` if no positional corresponds to `name` or if any
+    /// of the values cannot be cast to type `T`.
+    pub fn positionals_of<T: FromStr>(&self, name: &str) -> Result<Vec<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.positional_values.get(name)
+            .ok_or_else(|| no_value(name))
+            .and_then(|values| values.iter().map(|value| parse_value(name, value)).collect())
+            .map_err(|error| self.locate(error))
+    }
+
+    /// Restricts the values of the `Opt` identified by `opt_name` to `values`, which are
+    /// also listed in the usage message and offered by shell completions.
+    pub fn possible_values(&mut self, opt_name: &str, values: &[&str]) -> &mut Args {
+        match self.opts.get_mut(opt_name) {
+            Some(opt) => {
+                opt.annotate(&format!("[possible values: {}]", values.join(", ")));
+                self.possible_values.insert(opt_name.to_string(),
+                    values.iter().map(|value| value.to_string()).collect());
+            },
+            None => { warn!("Option '{}' is not registered, ignoring possible values", opt_name); }
+        }
+
+        self
+    }
+
+    /// Declares that the `Opt` identified by `opt_name` must be given whenever the
+    /// `Opt` identified by `other` has the provided `value`.
+    pub fn required_if_eq(&mut self, opt_name: &str, other: &str, value: &str) -> &mut Args {
+        self.register_rule(Rule::RequiredIfEq(opt_name.to_string(), other.to_string(), value.to_string()));
+
+        self
+    }
+
+    /// Declares that the `Opt` identified by `opt_name` must be given unless the
+    /// `Opt` identified by `other` is given instead.
+    pub fn required_unless_present(&mut self, opt_name: &str, other: &str) -> &mut Args {
+        self.register_rule(Rule::RequiredUnlessPresent(opt_name.to_string(), other.to_string()));
+
+        self
+    }
+
+    /// Declares that the `Opt` identified by `opt_name` can only be given along with
+    /// the `Opt` identified by `other`, e.g. `--key` requires `--cert`.
+    pub fn requires(&mut self, opt_name: &str, other: &str) -> &mut Args {
+        self.register_rule(Rule::Requires(opt_name.to_string(), other.to_string()));
+
+        self
+    }
+
+    /// Enables or disables the expansion of response files. When enabled, any argument
+    /// of the form `@path` is replaced by the arguments contained in the file at `path`,
+    /// which are separated by whitespace and may be quoted, escaped, commented with `#`
+    /// or include further response files relative to the including file.
+    pub fn response_files(&mut self, enabled: bool) -> &mut Args {
+        self.response_files = enabled;
+
+        self
+    }
+
     /// Generates a one-line usage summary from the registered options.
     pub fn short_usage(&self) -> String {
-        self.options.short_usage(&self.program_name)
+        let mut short_usage = format!("Usage: {}", self.program_name);
+        let mut rendered = BTreeSet::new();
+        for opt_name in self.opt_names.iter().filter(|opt_name| !self.deprecations.contains_key(*opt_name)) {
+            let mut groups = self.groups.iter().filter(|group| group.members().contains(opt_name)).peekable();
+            if groups.peek().is_none() {
+                short_usage.push_str(&format!(" {}", self.opts[opt_name].short_usage()));
+            }
+            // Render each group once, in place of its first registered member
+            for group in groups.filter(|group| rendered.insert(group.name())) {
+                let fragment = group.short_usage(|member| self.opts[member].long_usage());
+                short_usage.push_str(&format!(" {}", fragment));
+            }
+        }
+        for positional in &self.positionals {
+            short_usage.push_str(&format!(" {}", positional.short_usage()));
+        }
+        if self.has_subcommands() { short_usage.push_str(" COMMAND [ARGS].."); }
+        short_usage
+    }
+
+    /// Registers a subcommand and returns its `Args` so that options, positionals
+    /// and nested subcommands may be registered on it. When subcommands are registered
+    /// the first free argument selects the subcommand and all remaining arguments are
+    /// parsed by it; positionals registered on this `Args` are not matched.
+    ///
+    /// * `name` - The name of the subcommand, e.g. `"build"` for `program build`
+    /// * `desc` - A description of the subcommand for the usage message
+    pub fn subcommand(&mut self, name: &str, desc: &str) -> &mut Args {
+        if self.subcommand_names.contains(&name.to_string()) {
+            warn!("{} is already registered, reusing", name);
+        } else {
+            debug!("Registering subcommand '{}'", name);
+            let program_name = format!("{} {}", self.program_name, name);
+            self.subcommand_names.push(name.to_string());
+            self.subcommands.insert(name.to_string(), Args::new(&program_name, desc));
+        }
+
+        self.subcommands.get_mut(name).unwrap()
+    }
+
+    /// Returns the parsed `Args` of the subcommand matched during the last parse, if any.
+    pub fn subcommand_args(&self) -> Option<&Args> {
+        self.subcommand.as_ref().and_then(|name| self.subcommands.get(name))
+    }
+
+    /// Returns the name of the subcommand matched during the last parse, if any.
+    pub fn subcommand_name(&self) -> Option<&str> {
+        self.subcommand.as_deref()
     }
 
     /// Generates a verbose usage summary from the registered options.
     pub fn usage(&self) -> String {
-        if !self.has_options() { return format!("{}\n", self.description); }
-        self.options.usage(&self.description)
+        let width = self.usage_width();
+        let mut usage = format!("{}\n", self.description);
+        if let Some(ref before_help) = self.before_help { usage.push_str(&format!("\n{}\n", before_help)); }
+
+        // Align long names when some, but not all, options have a short name
+        let any_short = self.opts.values().any(|opt| !opt.flag().is_empty());
+        let mut sections: Vec<(&str, Vec<(String, String)>)> = Vec::new();
+        for opt_name in &self.opt_names {
+            let opt = &self.opts[opt_name];
+            let term = match opt.flag() {
+                ref flag if !flag.is_empty() => format!("-{}, {}", flag, opt.long_usage()),
+                _ if any_short => format!("    {}", opt.long_usage()),
+                _ => opt.long_usage()
+            };
+
+            // Sections are listed in the order their first option was registered
+            let heading = self.headings.get(opt_name).map_or(DEFAULT_HEADING, |heading| heading.as_str());
+            match sections.iter_mut().find(|&&mut (title, _)| title == heading) {
+                Some(&mut (_, ref mut rows)) => rows.push((term, opt.usage_desc())),
+                None => sections.push((heading, vec!((term, opt.usage_desc()))))
+            }
+        }
+        for (heading, rows) in sections {
+            usage.push_str(&help::section(heading, &rows, USAGE_COLUMN, width));
+        }
+
+        if self.has_positionals() {
+            let rows = self.positionals.iter()
+                .map(|positional| (positional.hint(), positional.desc()))
+                .collect::<Vec<(String, String)>>();
+            usage.push_str(&help::section("Arguments", &rows, USAGE_COLUMN, width));
+        }
+
+        if self.has_subcommands() {
+            let rows = self.subcommand_names.iter()
+                .map(|name| (name.to_string(), self.subcommands[name].description.to_string()))
+                .collect::<Vec<(String, String)>>();
+            usage.push_str(&help::section("Commands", &rows, USAGE_COLUMN, width));
+        }
+        if let Some(ref after_help) = self.after_help { usage.push_str(&format!("\n{}\n", after_help)); }
+        usage
+    }
+
+    /// Registers a flag which, when given, makes `parse` return `ParseOutcome::Version`
+    /// with the program name followed by `version`. The flag is ignored unless it has a `long_name`.
+    pub fn version_flag(&mut self, short_name: &str, long_name: &str, desc: &str, version: &str) -> &mut Args {
+        if long_name.is_empty() {
+            warn!("Version flag '{}' has no long name, ignoring", short_name);
+            return self;
+        }
+
+        self.flag(short_name, long_name, desc);
+        self.version = Some((long_name.to_string(), version.to_string()));
+
+        self
+    }
+
+    /// Registers `alias` as an alternative name for the `Opt` identified by `opt_name`
+    /// which, unlike those registered with `alias`, is listed in the usage message.
+    pub fn visible_alias(&mut self, opt_name: &str, alias: &str) -> &mut Args {
+        self.register_alias(opt_name, alias, true);
+
+        self
+    }
+
+    /// Returns the warnings recorded during the last parse, e.g. for deprecated options.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
     }
 
     /// Retrieves the optional value of the `Opt` identified by `opt_name`, casts it to
@@ -294,10 +941,11 @@ impl Args {
     /// # Failures
     ///
     /// See `validated_value_of`
-    pub fn optional_validated_value_of<T>(&self, opt_name: &str, validations: &[Box<Validation<T=T>>])
-                                          -> Result<Option<T>, ArgsError> where T: FromStr {
+    pub fn optional_validated_value_of<T>(&self, opt_name: &str, validations: &[Box<dyn Validation<T=T>>])
+                                          -> Result<Option<T>, ArgsError>
+                                          where T: FromStr, T::Err: Into<Box<dyn Error + Send + Sync>> {
         if self.has_value(opt_name) {
-            Ok(Some(try!(self.validated_value_of::<T>(opt_name, validations))))
+            Ok(Some(self.validated_value_of::<T>(opt_name, validations)?))
         } else {
             Ok(None)
         }
@@ -309,9 +957,10 @@ impl Args {
     /// # Failures
     ///
     /// See `value_of`
-    pub fn optional_value_of<T: FromStr>(&self, opt_name: &str) -> Result<Option<T>, ArgsError> {
+    pub fn optional_value_of<T: FromStr>(&self, opt_name: &str) -> Result<Option<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
         if self.has_value(opt_name) {
-            Ok(Some(try!(self.value_of::<T>(opt_name))))
+            Ok(Some(self.value_of::<T>(opt_name)?))
         } else {
             Ok(None)
         }
@@ -323,67 +972,701 @@ impl Args {
     /// # Failures
     ///
     /// Returns `Err(ArgsError)` if no `Opt` correspond to `opt_name`, if the value cannot
-    /// be cast to type `T` or if any validation is considered invalid.
-    pub fn validated_value_of<T>(&self, opt_name: &str, validations: &[Box<Validation<T=T>>])
-        -> Result<T, ArgsError> where T: FromStr {
+    /// be cast to type `T` or if any validation is considered invalid. The error of the
+    /// failing `Validation` is available as its `source()`.
+    pub fn validated_value_of<T>(&self, opt_name: &str, validations: &[Box<dyn Validation<T=T>>])
+        -> Result<T, ArgsError>
+        where T: FromStr, T::Err: Into<Box<dyn Error + Send + Sync>> {
         // If the value does not have an error, run validations
         self.value_of::<T>(opt_name).and_then(|value| {
+            let mut errors = Vec::new();
             for validation in validations {
-                // If any validations fail, break the loop and return the error
-                if validation.is_invalid(&value) { return Err(validation.error(&value)); }
+                // If any validations fail, break the loop unless collecting errors
+                if validation.is_invalid(&value) {
+                    let error = validation.error(&value);
+                    let raw = self.values.get(opt_name).and_then(|values| values.last()).map_or("", |raw| raw);
+                    self.fail(&mut errors, ArgsError::new(opt_name, &format!("invalid value '{}': {}", raw, error))
+                        .with_kind(ErrorKind::Validation)
+                        .with_opt_name(opt_name)
+                        .with_value(raw)
+                        .with_source(error))?;
+                }
             }
 
-            Ok(value)
+            if errors.is_empty() { Ok(value) } else { Err(ArgsError::multiple(opt_name, errors)) }
         })
     }
 
     /// Retrieves the value for the `Opt` identified by `opt_name` and casts it to
-    /// the type specified by `T`.
+    /// the type specified by `T`. If the option was given multiple times the last
+    /// value is used.
     ///
     /// # Failures
     ///
     /// Returns `Err(ArgsError)` if no `Opt` corresponds to `opt_name` or if the
-    /// value cannot be cast to type `T`.
-    pub fn value_of<T: FromStr>(&self, opt_name: &str) -> Result<T, ArgsError> {
-        self.values.get(opt_name).ok_or(
-            ArgsError::new(opt_name, "does not have a value")
-        ).and_then(|value_string| {
-            T::from_str(value_string).or(
-                Err(ArgsError::new(opt_name, &format!("unable to parse '{}'", value_string)))
-            )
-        })
+    /// value cannot be cast to type `T`, in which case the `T::Err` is its `source()`.
+    pub fn value_of<T: FromStr>(&self, opt_name: &str) -> Result<T, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.values.get(opt_name).and_then(|values| values.last())
+            .ok_or_else(|| no_value(opt_name))
+            .and_then(|value_string| parse_value(opt_name, value_string))
+            .map_err(|error| self.locate(error))
     }
 
     /// Retrieves a vector of values for the `Opt` identified by `opt_name` and
-    /// casts each of them to the type specified by `T`.
+    /// casts each of them to the type specified by `T`. Values are returned exactly
+    /// as they were given, one per occurrence unless the option is delimited.
     ///
     /// # Failures
     ///
     /// Returns `Err(ArgsError)` if no `Opt` corresponds to `opt_name` or if any
     /// of the values cannot be cast to type `T`.
-    pub fn values_of<T: FromStr>(&self, opt_name: &str) -> Result<Vec<T>, ArgsError> {
-        self.values.get(opt_name).ok_or(
-            ArgsError::new(opt_name, "does not have a value")
-        ).and_then(|values_str| {
-            values_str.split(SEPARATOR).map(|value| {
-                T::from_str(value).or(
-                    Err(ArgsError::new(opt_name, &format!("unable to parse '{}'", value)))
-                )
-            }).collect()
-        })
+    pub fn values_of<T: FromStr>(&self, opt_name: &str) -> Result<Vec<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.values.get(opt_name)
+            .ok_or_else(|| no_value(opt_name))
+            .and_then(|values| values.iter().map(|value| parse_value(opt_name, value)).collect())
+            .map_err(|error| self.locate(error))
     }
 
     // Private instance methods
-    fn register_opt(&mut self, opt: Box<Opt>) {
+    fn apply_config(&mut self) -> Result<(), ArgsError> {
+        let mut entries = mem::take(&mut self.config_entries);
+        if let Some(path) = self.config_path() { entries.extend(config::read(&path)?); }
+
+        for mut entry in entries {
+            let location = format!("{}:{}", entry.file, entry.line);
+
+            // Entries within a section belong to the subcommand of the same name
+            if let Some(section) = entry.section.take() {
+                match self.subcommands.get_mut(&section) {
+                    Some(subcommand) => { subcommand.config_entries.push(entry); continue; },
+                    None => {
+                        return Err(ArgsError::new(&location, &format!("unknown section '{}'", section))
+                            .with_kind(ErrorKind::Config));
+                    }
+                }
+            }
+
+            let values = match self.opts.get(&entry.key) {
+                Some(opt) => opt.parse_config(&entry.values).ok_or_else(|| {
+                    ArgsError::new(&location, &format!("invalid value for key '{}'", entry.key))
+                        .with_kind(ErrorKind::Config)
+                        .with_opt_name(&entry.key)
+                        .with_value(&entry.values.join(", "))
+                })?,
+                None => {
+                    return Err(ArgsError::new(&location, &format!("unknown key '{}'", entry.key))
+                        .with_kind(ErrorKind::Config));
+                }
+            };
+
+            // Values from the command line and environment take precedence
+            match self.sources.get(&entry.key) {
+                Some(&Source::Cli) | Some(&Source::Env) => {},
+                _ => {
+                    self.values.insert(entry.key.to_string(), values);
+                    self.sources.insert(entry.key, Source::Config(location));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_deprecations(&mut self) {
+        self.warnings.clear();
+        for (opt_name, deprecation) in &self.deprecations {
+            if !self.is_given(opt_name) { continue; }
+
+            let mut warning = format!("Option '{}' is deprecated: {}", opt_name, deprecation.message);
+            if let Some(ref replacement) = deprecation.replacement {
+                warning.push_str(&format!(" (use '{}' instead)", replacement));
+
+                // Forward the values unless the replacement was given as well
+                if !self.is_given(replacement) {
+                    if let Some(values) = self.values.get(opt_name).cloned() {
+                        self.values.insert(replacement.to_string(), values);
+                    }
+                    if let Some(source) = self.sources.get(opt_name).cloned() {
+                        self.sources.insert(replacement.to_string(), source);
+                    }
+                    if self.valueless.contains(opt_name) { self.valueless.insert(replacement.to_string()); }
+                }
+            }
+            warn!("{}", warning);
+            self.warnings.push(warning);
+        }
+    }
+
+    fn completion_command(&self) -> completions::Command {
+        completions::Command {
+            path: self.program_name.split_whitespace().map(|name| name.to_string()).collect(),
+            desc: self.description.to_string(),
+            flags: self.opt_names.iter().map(|opt_name| {
+                let opt = &self.opts[opt_name];
+                let (mut shorts, mut longs) = self.visible_aliases(opt.as_ref()).into_iter()
+                    .partition::<Vec<String>, _>(|alias| alias.chars().count() == 1);
+                if !opt.flag().is_empty() { shorts.insert(0, opt.flag()); }
+                longs.splice(0..0, opt.long_names());
+                completions::Flag {
+                    shorts,
+                    longs,
+                    desc: opt.desc(),
+                    hint: if opt.takes_value() { Some(opt.hint()) } else { None },
+                    values: self.possible_values.get(opt_name).cloned().unwrap_or_default()
+                }
+            }).collect(),
+            has_positionals: self.has_positionals(),
+            commands: self.subcommand_names.iter()
+                .map(|name| self.subcommands[name].completion_command())
+                .collect()
+        }
+    }
+
+    fn config_path(&self) -> Option<PathBuf> {
+        let option = self.config_option.as_ref().and_then(|opt_name| {
+            self.values.get(opt_name).and_then(|values| values.last())
+                .map(|path| (PathBuf::from(path), self.sources[opt_name].clone()))
+        });
+
+        match option {
+            // An explicitly given path must exist
+            Some((path, Source::Cli)) | Some((path, Source::Env)) => Some(path),
+            Some((path, _)) => Some(path).filter(|path| path.exists()),
+            None => self.config_file.clone().filter(|path| path.exists())
+        }
+    }
+
+    fn env_values(&self, opt: &dyn Opt) -> Result<Option<Vec<String>>, ArgsError> {
+        let var = match self.envs.get(&opt.name()) {
+            Some(var) => var,
+            None => { return Ok(None); }
+        };
+
+        match (self.env_lookup)(var) {
+            Some(value) => opt.parse_env(&value).map(Some).ok_or_else(|| {
+                ArgsError::new(SCOPE_PARSE, &format!("Invalid value '{}' for environment variable '{}'", value, var))
+                    .with_kind(ErrorKind::ParseValue)
+                    .with_opt_name(&opt.name())
+                    .with_value(&value)
+            }),
+            None => Ok(None)
+        }
+    }
+
+    fn has_arg(&self, name: &str) -> HasArg {
+        self.opts.values()
+            .find(|opt| opt.flag() == name || opt.long_names().iter().any(|long| long == name))
+            .map_or(HasArg::No, |opt| opt.has_arg())
+    }
+
+    /// Returns a `bool` indicating whether or not an option was given explicitly,
+    /// i.e. not by its default, and was not switched off.
+    fn is_given(&self, opt_name: &str) -> bool {
+        let explicit = match self.sources.get(opt_name) {
+            Some(&Source::Default) | None => self.valueless.contains(opt_name),
+            Some(_) => true
+        };
+        // Only boolean flags can be switched off, other options may have any value
+        let switched_off = self.opts.get(opt_name).is_some_and(|opt| opt.is_switch()) &&
+            self.values.get(opt_name).is_some_and(|values| values.iter().any(|value| value == "false"));
+        explicit && !switched_off
+    }
+
+    /// Returns `error` to fail immediately, or records it in `errors` when collecting errors.
+    fn fail(&self, errors: &mut Vec<ArgsError>, error: ArgsError) -> Result<(), ArgsError> {
+        let error = self.locate(error);
+        if !self.collect_errors { return Err(error); }
+
+        errors.push(error);
+        Ok(())
+    }
+
+    fn locate(&self, error: ArgsError) -> ArgsError {
+        if error.position().is_some() { return error; }
+
+        // Point at the arguments which gave the option its value, or else at the value itself,
+        // falling back on the configuration file entry which gave it
+        let span = match error.opt_name().and_then(|opt_name| self.opts.get(opt_name)) {
+            Some(opt) => self.opt_span(opt.as_ref(), error.value()),
+            None => error.value()
+                .and_then(|value| self.args.iter().rposition(|arg| arg == value))
+                .map(|index| (index, index))
+        };
+        match span {
+            Some((first, last)) => self.point_at(error, first, last),
+            None => match error.opt_name().and_then(|opt_name| self.sources.get(opt_name)) {
+                Some(Source::Config(location)) if error.location().is_none() => {
+                    let location = location.to_string();
+                    error.with_location(&location)
+                },
+                _ => error
+            }
+        }
+    }
+
+    fn opt_span(&self, opt: &dyn Opt, value: Option<&str>) -> Option<(usize, usize)> {
+        if self.sources.get(&opt.name()) != Some(&Source::Cli) { return None; }
+
+        let args = &self.args;
+        let mut occurrences = self.names_of(opt).iter()
+            .flat_map(|name| recovery::occurrences(args, name, |name| self.has_arg(name)))
+            .map(|(index, offset)| {
+                let attached = match offset {
+                    Some(offset) => offset + opt.flag().len() < args[index].len(),
+                    None => args[index].contains('=')
+                };
+                let last = if opt.takes_value() && !attached && index + 1 < args.len() { index + 1 } else { index };
+                (index, last)
+            })
+            .collect::<Vec<(usize, usize)>>();
+        occurrences.sort_by(|a, b| b.cmp(a));
+
+        // Prefer the last occurrence which gave the value, if there is one
+        occurrences.iter()
+            .find(|&&(first, last)| value.is_none_or(|value| args[first..=last].iter().any(|arg| arg.contains(value))))
+            .or_else(|| occurrences.first())
+            .cloned()
+    }
+
+    /// Points `error` at the arguments from `first` to `last` as they were given on the
+    /// command line, noting the response file they were read from, if any.
+    fn point_at(&self, error: ArgsError, first: usize, last: usize) -> ArgsError {
+        let error = error.with_span(&self.command_line, self.positions[first], self.positions[last]);
+        match self.locations[first] {
+            Some(ref location) => error.with_location(location),
+            None => error
+        }
+    }
+
+    /// Returns every name `opt` may be given by: its short name, long names and aliases.
+    fn names_of(&self, opt: &dyn Opt) -> Vec<String> {
+        let mut names = opt.long_names();
+        if !opt.flag().is_empty() { names.push(opt.flag()); }
+        names.extend(self.aliases.iter()
+            .filter(|alias| alias.target == opt.name() || names.contains(&alias.target))
+            .map(|alias| alias.name.to_string())
+            .collect::<Vec<String>>());
+        names
+    }
+
+    fn options(&self) -> Options {
+        let mut options = Options::new();
+
+        // Stop at the first free argument so that the subcommand receives its own options
+        if self.has_subcommands() { options.parsing_style(ParsingStyle::StopAtFirstFree); }
+
+        for opt_name in &self.opt_names {
+            self.opts[opt_name].register(&mut options);
+        }
+        options
+    }
+
+    fn register_opt(&mut self, opt: Box<dyn Opt>) {
         if !self.opt_names.contains(&opt.name()) {
             debug!("Registering {}", opt);
-            opt.register(&mut self.options);
             self.opt_names.push(opt.name().to_string());
+            if let Some(ref heading) = self.heading { self.headings.insert(opt.name(), heading.to_string()); }
             self.opts.insert(opt.name().to_string(), opt);
         } else {
             warn!("{} is already registered, ignoring", opt.name());
         }
     }
+
+    fn parse_args(&mut self,
+            raw_args: Vec<OsString>,
+            command_line: Vec<String>,
+            mut positions: Vec<usize>,
+            mut locations: Vec<Option<String>>) -> Result<ParseOutcome, ArgsError> {
+        debug!("Parsing args for '{}'", self.program_name);
+
+        // Expand any response files and resolve aliases before handing the arguments to getopts,
+        // keeping the position in the command line each argument was given at, and the response
+        // file it was read from, so that errors point at what was typed
+        let mut args = raw_args.iter().map(|arg| arg.to_string_lossy().into_owned()).collect::<Vec<String>>();
+        let rewrite = self.response_files || !self.aliases.is_empty() || self.collect_errors;
+        if self.response_files {
+            let expanded = response::expand(args)?;
+            positions = expanded.iter().map(|expanded| positions[expanded.index]).collect();
+            locations = expanded.iter()
+                .map(|expanded| expanded.location.clone().or_else(|| locations[expanded.index].clone()))
+                .collect();
+            args = expanded.into_iter().map(|expanded| expanded.arg).collect();
+        }
+        if !self.aliases.is_empty() {
+            let resolved = aliases::resolve(args, &self.aliases, |name| self.has_arg(name), self.has_subcommands());
+            positions = resolved.iter().map(|&(_, index)| positions[index]).collect();
+            locations = resolved.iter().map(|&(_, index)| locations[index].clone()).collect();
+            args = resolved.into_iter().map(|(arg, _)| arg).collect();
+        }
+        self.command_line = command_line;
+        self.positions = positions;
+        self.locations = locations;
+
+        // Get matches, dropping each rejected option and trying again when collecting errors
+        let mut errors = Vec::new();
+        let matches = loop {
+            let parsed = if rewrite { self.options().parse(&args) } else { self.options().parse(&raw_args) };
+            let fail = match parsed {
+                Ok(matches) => { break matches; },
+                Err(fail) => fail
+            };
+
+            let (name, from_end) = match fail {
+                Fail::ArgumentMissing(ref name) |
+                Fail::OptionDuplicated(ref name) => (name.to_string(), true),
+                Fail::OptionMissing(ref name) |
+                Fail::UnrecognizedOption(ref name) |
+                Fail::UnexpectedArgument(ref name) => (name.to_string(), false)
+            };
+            // getopts names an option by its long name, which may have been given by another name
+            let names = match self.opts.values().find(|opt| opt.flag() == name || opt.long_names().contains(&name)) {
+                Some(opt) => self.names_of(opt.as_ref()),
+                None => vec!(name)
+            };
+            let found = names.iter().filter_map(|name| {
+                recovery::find(&args, name, from_end, |name| self.has_arg(name)).map(|index| (index, name))
+            });
+            let found = if from_end { found.max_by_key(|&(index, _)| index) } else { found.min_by_key(|&(index, _)| index) };
+            let mut error = self.parse_error(fail);
+            if let Some((index, _)) = found { error = self.point_at(error, index, index); }
+            errors.push(error);
+
+            match found.filter(|_| self.collect_errors) {
+                Some((index, name)) => {
+                    if recovery::remove(&mut args, index, name, |name| self.has_arg(name)) {
+                        self.positions.remove(index);
+                        self.locations.remove(index);
+                    }
+                },
+                None => { return Err(ArgsError::multiple(SCOPE_PARSE, errors)); }
+            }
+        };
+        self.args = args;
+
+        // Answer requests for help or the version before any values are validated
+        if errors.is_empty() && self.help_flag.as_ref().is_some_and(|help_flag| matches.opt_present(help_flag)) {
+            return Ok(ParseOutcome::Help(self.full_usage()));
+        }
+        if let Some((ref version_flag, ref version)) = self.version.as_ref().filter(|_| errors.is_empty()) {
+            if matches.opt_present(version_flag) {
+                return Ok(ParseOutcome::Version(format!("{} {}", self.program_name, version)));
+            }
+        }
+
+        // Find matches and store the values (or a default)
+        for opt_name in &self.opt_names {
+            let option = self.opts.get(opt_name);
+            if option.is_none() {
+                return Err(fail_error(Fail::UnrecognizedOption(opt_name.to_string())));
+            }
+
+            let opt = option.unwrap();
+            if opt.is_valueless(&matches) { self.valueless.insert(opt_name.to_string()); }
+
+            // Prefer the command line, then the environment and finally the default
+            let (values, source) = if opt.is_present(&matches) {
+                (opt.parse(&matches), Source::Cli)
+            } else {
+                match self.env_values(opt.as_ref()) {
+                    Ok(Some(values)) => (Some(values), Source::Env),
+                    Ok(None) => (opt.parse(&matches), Source::Default),
+                    Err(error) => {
+                        self.fail(&mut errors, error)?;
+                        (opt.parse(&matches), Source::Default)
+                    }
+                }
+            };
+            if let Some(values) = values.filter(|values| !values.is_empty()) {
+                self.values.insert(opt_name.to_string(), values);
+                self.sources.insert(opt_name.to_string(), source);
+            }
+        }
+
+        // Fill in any values from the configuration file which were not given
+        if let Err(error) = self.apply_config() { self.fail(&mut errors, error)?; }
+        self.apply_deprecations();
+
+        for opt_name in &self.opt_names {
+            if self.opts[opt_name].is_required() && !self.has_value(opt_name) && !self.valueless.contains(opt_name) {
+                let msg = Fail::OptionMissing(opt_name.to_string()).to_string();
+                self.fail(&mut errors, ArgsError::new(SCOPE_PARSE, &msg)
+                    .with_kind(ErrorKind::MissingRequired)
+                    .with_opt_name(opt_name))?;
+            }
+        }
+        for (opt_name, possible_values) in &self.possible_values {
+            let values = self.values.get(opt_name).map_or(&[][..], |values| &values[..]);
+            for value in values.iter().filter(|value| !possible_values.contains(value)) {
+                let msg = format!("Invalid value '{}' for option '{}' (possible values: {})",
+                    value, opt_name, possible_values.join(", "));
+                self.fail(&mut errors, ArgsError::new(SCOPE_PARSE, &msg)
+                    .with_kind(ErrorKind::Validation)
+                    .with_opt_name(opt_name)
+                    .with_value(value))?;
+            }
+        }
+        for group in &self.groups {
+            if let Err(error) = group.check(|opt_name| self.is_given(opt_name)) { self.fail(&mut errors, error)?; }
+        }
+        for rule in &self.rules {
+            let checked = rule.check(|opt_name| self.is_given(opt_name), |opt_name| self.value_of::<String>(opt_name).ok());
+            if let Err(error) = checked { self.fail(&mut errors, error)?; }
+        }
+
+        // Dispatch to the subcommand named by the first free argument
+        let mut free = matches.free;
+        if self.has_subcommands() {
+            debug!("Args: {:?}", self.values);
+            return match self.parse_subcommand(free) {
+                Ok(ParseOutcome::Parsed) if !errors.is_empty() => Err(ArgsError::multiple(SCOPE_PARSE, errors)),
+                Err(error) if !errors.is_empty() => {
+                    errors.extend(error);
+                    Err(ArgsError::multiple(SCOPE_PARSE, errors))
+                },
+                outcome => outcome
+            };
+        }
+
+        // Assign the free arguments to the registered positionals
+        for positional in &self.positionals {
+            match positional.parse(&mut free) {
+                Some(values) => { self.positional_values.insert(positional.name(), values); },
+                None => {
+                    if positional.is_required() {
+                        self.fail(&mut errors, ArgsError::new(SCOPE_PARSE,
+                            &format!("Required argument '{}' missing", positional.name()))
+                            .with_kind(ErrorKind::MissingRequired)
+                            .with_opt_name(&positional.name()))?;
+                    }
+                    self.positional_values.insert(positional.name(), Vec::new());
+                }
+            }
+        }
+        // Leftover arguments are only an error if positionals have been declared
+        if let Some(unexpected) = free.first().filter(|_| self.has_positionals()) {
+            self.fail(&mut errors, ArgsError::new(SCOPE_PARSE, &format!("Unexpected argument '{}'", unexpected))
+                .with_kind(ErrorKind::UnexpectedArgument)
+                .with_value(unexpected))?;
+        }
+        if !errors.is_empty() { return Err(ArgsError::multiple(SCOPE_PARSE, errors)); }
+
+        debug!("Args: {:?}", self.values);
+        debug!("Positionals: {:?}", self.positional_values);
+        Ok(ParseOutcome::Parsed)
+    }
+
+    fn parse_error(&self, fail: Fail) -> ArgsError {
+        match fail {
+            Fail::UnrecognizedOption(name) => {
+                let hint = suggestions::hint(&self.similar_opt_names(&name));
+                let msg = format!("{}{}", Fail::UnrecognizedOption(name.to_string()), hint);
+                ArgsError::new(SCOPE_PARSE, &msg)
+                    .with_kind(ErrorKind::UnrecognizedOption)
+                    .with_opt_name(&name)
+            },
+            fail => fail_error(fail)
+        }
+    }
+
+    fn parse_subcommand(&mut self, mut free: Vec<String>) -> Result<ParseOutcome, ArgsError> {
+        if free.is_empty() { return Ok(ParseOutcome::Parsed); }
+        let name = free.remove(0);
+
+        // Treat `help [command]` as a request for usage unless it is a registered subcommand
+        if name == HELP_COMMAND && !self.subcommands.contains_key(HELP_COMMAND) {
+            return match free.first() {
+                Some(name) => match self.subcommands.get(name) {
+                    Some(subcommand) => Ok(ParseOutcome::Help(subcommand.full_usage())),
+                    None => Err(self.locate(unrecognized_command(name, &self.subcommand_names)))
+                },
+                None => Ok(ParseOutcome::Help(self.full_usage()))
+            };
+        }
+
+        if !self.subcommands.contains_key(&name) {
+            return Err(self.locate(unrecognized_command(&name, &self.subcommand_names)));
+        }
+        let command_line = self.command_line.clone();
+        let first = self.args.len() - free.len();
+        let positions = self.positions[first..].to_vec();
+        let locations = self.locations[first..].to_vec();
+        let subcommand = self.subcommands.get_mut(&name).unwrap();
+        self.subcommand = Some(name);
+        subcommand.env_lookup = self.env_lookup.clone();
+        subcommand.collect_errors |= self.collect_errors;
+
+        // Answer `command --help` with the subcommand's usage unless it defines its own help
+        if subcommand.requests_help(&free) {
+            return Ok(ParseOutcome::Help(subcommand.full_usage()));
+        }
+
+        subcommand.parse_args(free.into_iter().map(OsString::from).collect(), command_line, positions, locations)
+    }
+
+    fn reference_page(&self) -> reference::Page {
+        let options = self.opt_names.iter().map(|opt_name| {
+            let opt = &self.opts[opt_name];
+            let mut names = opt.long_names().iter().map(|long| format!("--{}", long)).collect::<Vec<String>>();
+            if !opt.flag().is_empty() { names.insert(0, format!("-{}", opt.flag())); }
+            names.extend(self.visible_aliases(opt.as_ref()).iter().map(|alias| {
+                let dashes = if alias.chars().count() == 1 { "-" } else { "--" };
+                format!("{}{}", dashes, alias)
+            }));
+            let hint = match opt.hint() {
+                ref hint if hint.is_empty() || opt.takes_value() => hint.to_string(),
+                hint => format!("[{}]", hint)
+            };
+            reference::Entry {
+                id: opt_name.to_string(),
+                names,
+                hint,
+                desc: opt.desc(),
+                default: opt.default_value(),
+                required: opt.is_required()
+            }
+        });
+        let arguments = self.positionals.iter().map(|positional| reference::Entry {
+            id: positional.name(),
+            names: Vec::new(),
+            hint: positional.hint(),
+            desc: positional.desc(),
+            default: None,
+            required: positional.is_required()
+        });
+        let commands = self.subcommand_names.iter().map(|name| reference::Entry {
+            id: name.to_string(),
+            names: vec!(name.to_string()),
+            hint: String::new(),
+            desc: self.subcommands[name].description.to_string(),
+            default: None,
+            required: false
+        });
+
+        let short_usage = self.short_usage();
+        reference::Page {
+            name: self.program_name.to_string(),
+            desc: self.description.to_string(),
+            synopsis: short_usage.trim_start_matches("Usage: ").to_string(),
+            options: options.collect(),
+            arguments: arguments.collect(),
+            commands: commands.collect(),
+            sections: self.man_sections.clone()
+        }
+    }
+
+    fn register_alias(&mut self, opt_name: &str, alias: &str, visible: bool) {
+        let taken = self.opts.values().any(|opt| opt.flag() == alias || opt.name() == alias) ||
+            self.aliases.iter().any(|existing| existing.name == alias);
+        if alias.is_empty() || taken {
+            warn!("Alias '{}' is empty or already in use, ignoring", alias);
+            return;
+        }
+
+        let opt = match self.opts.get_mut(opt_name) {
+            Some(opt) => opt,
+            None => {
+                warn!("Alias '{}' refers to '{}' which is not registered, ignoring", alias, opt_name);
+                return;
+            }
+        };
+        if visible {
+            let dashes = if alias.chars().count() == 1 { "-" } else { "--" };
+            opt.annotate(&format!("[alias: {}{}]", dashes, alias));
+        }
+
+        // Negatable flags may also be switched off by the negated form of a long alias
+        if let Some(negated) = opt.long_names().get(1).filter(|_| alias.chars().count() > 1) {
+            self.aliases.push(Alias {
+                name: format!("{}{}", NEGATION_PREFIX, alias),
+                target: negated.to_string(),
+                visible
+            });
+        }
+        self.aliases.push(Alias { name: alias.to_string(), target: opt_name.to_string(), visible });
+    }
+
+    fn register_group(&mut self, group: Group) {
+        for member in group.members() {
+            if !self.opts.contains_key(member) {
+                warn!("Group '{}' includes '{}' which is not registered, ignoring", group.name(), member);
+                return;
+            }
+        }
+
+        self.groups.push(group);
+    }
+
+    fn register_rule(&mut self, rule: Rule) {
+        let registered = {
+            let (opt_name, other) = rule.names();
+            self.opts.contains_key(opt_name) && self.opts.contains_key(other)
+        };
+
+        if registered {
+            for (opt_name, note) in rule.notes() {
+                self.opts.get_mut(&opt_name).unwrap().annotate(&note);
+            }
+            self.rules.push(rule);
+        } else {
+            warn!("{:?} relates an option which is not registered, ignoring", rule.names());
+        }
+    }
+
+    fn register_positional(&mut self, positional: Positional) {
+        if self.positionals.iter().any(|registered| registered.name() == positional.name()) {
+            warn!("{} is already registered, ignoring", positional.name());
+        } else if self.positionals.iter().any(|registered| registered.is_variadic()) {
+            warn!("{} follows a variadic positional, ignoring", positional.name());
+        } else if positional.is_required() && self.positionals.iter().any(|registered| !registered.is_required()) {
+            warn!("{} is required but follows an optional positional, ignoring", positional.name());
+        } else {
+            debug!("Registering positional '{}'", positional.name());
+            self.positionals.push(positional);
+        }
+    }
+
+    fn requests_help(&self, raw_args: &[String]) -> bool {
+        if self.opts.contains_key(HELP_COMMAND) { return false; }
+
+        let short_help = !self.opts.values().any(|opt| opt.flag() == "h");
+        raw_args.iter()
+            .take_while(|arg| *arg != "--")
+            .any(|arg| arg == "--help" || (short_help && arg == "-h"))
+    }
+
+    /// Returns the registered option names and aliases closest to `name`, with dashes.
+    fn similar_opt_names(&self, name: &str) -> Vec<String> {
+        let names = self.opts.values()
+            .flat_map(|opt| {
+                let mut names = opt.long_names();
+                names.push(opt.flag());
+                names
+            })
+            .chain(self.aliases.iter().map(|alias| alias.name.to_string()));
+        suggestions::closest(name, names).into_iter()
+            .map(|name| if name.chars().count() == 1 { format!("-{}", name) } else { format!("--{}", name) })
+            .collect()
+    }
+
+    /// Returns the width usage is wrapped to, which is `max_width` narrowed to the
+    /// terminal width if `COLUMNS` is set.
+    fn usage_width(&self) -> usize {
+        (self.env_lookup)(COLUMNS_VAR)
+            .and_then(|columns| columns.trim().parse::<usize>().ok())
+            .filter(|&columns| columns > 0)
+            .map_or(self.max_width, |columns| columns.min(self.max_width))
+    }
+
+    /// Returns the names of the visible aliases of `opt`.
+    fn visible_aliases(&self, opt: &dyn Opt) -> Vec<String> {
+        let names = opt.long_names();
+        self.aliases.iter()
+            .filter(|alias| alias.visible && (alias.target == opt.name() || names.contains(&alias.target)))
+            .map(|alias| alias.name.to_string())
+            .collect()
+    }
 }
 
 impl Display for Args {
@@ -391,9 +1674,9 @@ impl Display for Args {
         let mut display = String::new();
         display.push_str(&format!("{}\n{}",
             to_column("Args"), column_underline()));
-        for (key, value) in self.values.clone() {
+        for (key, values) in &self.values {
             display.push_str(&format!("\n{}\t{}",
-                to_column(&key), to_column(&value)));
+                to_column(key), to_column(&values.join(", "))));
         }
         write!(f, "{}", display)
     }
@@ -402,10 +1685,48 @@ impl Display for Args {
 // Private associated methods
 fn column_underline() -> String {
     let mut underline = String::new();
-    for _ in 0..COLUMN_WIDTH { underline.push_str("="); }
+    for _ in 0..COLUMN_WIDTH { underline.push('='); }
     underline
 }
 
+fn fail_error(fail: Fail) -> ArgsError {
+    let (kind, opt_name) = match fail {
+        Fail::ArgumentMissing(ref opt_name) => (ErrorKind::MissingArgument, opt_name),
+        Fail::UnrecognizedOption(ref opt_name) => (ErrorKind::UnrecognizedOption, opt_name),
+        Fail::OptionMissing(ref opt_name) => (ErrorKind::MissingRequired, opt_name),
+        Fail::OptionDuplicated(ref opt_name) => (ErrorKind::DuplicateOption, opt_name),
+        Fail::UnexpectedArgument(ref opt_name) => (ErrorKind::UnexpectedArgument, opt_name)
+    };
+    ArgsError::new(SCOPE_PARSE, &fail.to_string())
+        .with_kind(kind)
+        .with_opt_name(opt_name)
+}
+
+fn no_value(name: &str) -> ArgsError {
+    ArgsError::new(name, "does not have a value")
+        .with_kind(ErrorKind::NoValue)
+        .with_opt_name(name)
+}
+
+fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+    T::from_str(value).map_err(|error| {
+        let error = error.into();
+        ArgsError::new(name, &format!("unable to parse '{}': {}", value, error))
+            .with_kind(ErrorKind::ParseValue)
+            .with_opt_name(name)
+            .with_value(value)
+            .with_source(error)
+    })
+}
+
+fn unrecognized_command(name: &str, commands: &[String]) -> ArgsError {
+    let hint = suggestions::hint(&suggestions::closest(name, commands));
+    ArgsError::new(SCOPE_PARSE, &format!("Unrecognized command: '{}'{}", name, hint))
+        .with_kind(ErrorKind::UnrecognizedCommand)
+        .with_value(name)
+}
+
 fn to_column(string: &str) -> String {
     let mut string = string.to_string();
     string = if string.len() > COLUMN_WIDTH {
@@ -413,7 +1734,7 @@ fn to_column(string: &str) -> String {
         format!("{}...", string)
     } else { string };
     let mut spaces = String::new();
-    for _ in 0..(COLUMN_WIDTH - string.len()) { spaces.push_str(" "); }
+    for _ in 0..(COLUMN_WIDTH - string.len()) { spaces.push(' '); }
     format!("{}{}", string, spaces)
 }
 
@@ -0,0 +1,27 @@
+/// The state of an option whose argument is optional, e.g. `--color` vs `--color=never`.
+#[derive(Clone,Debug,PartialEq)]
+pub enum MaybeValue<T> {
+    /// The option was not given.
+    Absent,
+    /// The option was given without a value, carrying the implicit value if one is registered.
+    Present(Option<T>),
+    /// The option was given with a value, either on the command line, in the environment
+    /// or in a configuration file.
+    Value(T)
+}
+
+impl<T> MaybeValue<T> {
+    /// Returns a `bool` indicating whether or not the option was given.
+    pub fn is_present(&self) -> bool {
+        !matches!(*self, MaybeValue::Absent)
+    }
+
+    /// Converts into the explicit or implicit value, if there is one.
+    pub fn value(self) -> Option<T> {
+        match self {
+            MaybeValue::Absent => None,
+            MaybeValue::Present(implicit) => implicit,
+            MaybeValue::Value(value) => Some(value)
+        }
+    }
+}
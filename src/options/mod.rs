@@ -3,11 +3,8 @@ use std::fmt::{self,Debug,Display,Error,Formatter};
 
 #[cfg(test)] mod tst;
 
-use super::SEPARATOR as SEPARATOR;
-
-macro_rules! unsupported {
-    ( $str:expr ) => ( panic!("{} is not supported yet", $str) );
-}
+const ENV_DELIMITER: &str = ",";
+pub const NEGATION_PREFIX: &str = "no-";
 
 pub fn new(short_name: &str,
         long_name: &str,
@@ -15,8 +12,10 @@ pub fn new(short_name: &str,
         hint: &str,
         has_arg: HasArg,
         occur: Occur,
-        default: Option<String>) -> Box<Opt> {
-    if has_arg == HasArg::Maybe { unsupported!("HasArg::Maybe"); }
+        default: Option<String>) -> Box<dyn Opt> {
+    if has_arg == HasArg::Maybe {
+        return maybe(short_name, long_name, desc, hint, occur, default, None);
+    }
 
     if occur != Occur::Multi {
         Box::new(Single::new(short_name, long_name, desc, hint, has_arg, occur, default))
@@ -25,13 +24,193 @@ pub fn new(short_name: &str,
     }
 }
 
+pub fn count(short_name: &str,
+        long_name: &str,
+        desc: &str,
+        min: Option<usize>,
+        max: Option<usize>) -> Box<dyn Opt> {
+    Box::new(Count::new(short_name, long_name, desc, min, max))
+}
+
+pub fn delimited(short_name: &str,
+        long_name: &str,
+        desc: &str,
+        hint: &str,
+        delimiter: &str) -> Box<dyn Opt> {
+    let mut multi = Multi::new(short_name, long_name, desc, hint);
+    multi.delimiter = Some(delimiter.to_string());
+    Box::new(multi)
+}
+
+pub fn maybe(short_name: &str,
+        long_name: &str,
+        desc: &str,
+        hint: &str,
+        occur: Occur,
+        default: Option<String>,
+        implicit: Option<String>) -> Box<dyn Opt> {
+    let occur = if occur == Occur::Multi {
+        warn!("{} may only be given once as its argument is optional, ignoring Occur::Multi", long_name);
+        Occur::Optional
+    } else {
+        occur
+    };
+
+    let mut single = Single::new(short_name, long_name, desc, hint, HasArg::Maybe, occur, default);
+    single.implicit = implicit;
+    Box::new(single)
+}
+
+pub fn negatable(short_name: &str,
+        long_name: &str,
+        desc: &str,
+        default: bool) -> Box<dyn Opt> {
+    Box::new(Negatable::new(short_name, long_name, desc, default))
+}
+
 pub trait Opt: Send {
+    fn annotate(&mut self, note: &str);
+    fn default_value(&self) -> Option<String>;
+    fn desc(&self) -> String;
     fn flag(&self) -> String;
-    fn is_multi(&self) -> bool;
+    fn hint(&self) -> String;
+    fn is_present(&self, matches: &Matches) -> bool;
     fn is_required(&self) -> bool;
+    fn is_valueless(&self, matches: &Matches) -> bool;
+    fn long_usage(&self) -> String;
     fn name(&self) -> String;
-    fn parse(&self, matches: &Matches) -> Option<String>;
+    fn parse(&self, matches: &Matches) -> Option<Vec<String>>;
+    fn parse_config(&self, values: &[String]) -> Option<Vec<String>>;
+    fn parse_env(&self, value: &str) -> Option<Vec<String>>;
     fn register(&self, options: &mut Options);
+    fn short_usage(&self) -> String;
+    fn takes_value(&self) -> bool;
+
+    // Defaulted instance methods
+    fn has_arg(&self) -> HasArg {
+        if self.takes_value() { HasArg::Yes } else { HasArg::No }
+    }
+
+    fn is_switch(&self) -> bool {
+        false
+    }
+
+    fn long_names(&self) -> Vec<String> {
+        vec!(self.name())
+    }
+
+    fn usage_desc(&self) -> String {
+        self.desc()
+    }
+}
+
+struct Count {
+    short_name: String,
+    long_name: String,
+    desc: String,
+    min: Option<usize>,
+    max: Option<usize>
+}
+
+impl Count {
+    fn new(short_name: &str,
+            long_name: &str,
+            desc: &str,
+            min: Option<usize>,
+            max: Option<usize>) -> Self {
+        Count {
+            short_name: short_name.to_string(),
+            long_name: long_name.to_string(),
+            desc: desc.to_string(),
+            min,
+            max
+        }
+    }
+
+    fn cap(&self, count: usize) -> Vec<String> {
+        let count = self.min.map_or(count, |min| count.max(min));
+        let count = self.max.map_or(count, |max| count.min(max));
+        vec!(count.to_string())
+    }
+}
+
+impl Opt for Count {
+    fn annotate(&mut self, note: &str) {
+        self.desc = format!("{} {}", self.desc, note);
+    }
+
+    fn default_value(&self) -> Option<String> {
+        None
+    }
+
+    fn desc(&self) -> String {
+        self.desc.to_string()
+    }
+
+    fn flag(&self) -> String {
+        self.short_name.to_string()
+    }
+
+    fn hint(&self) -> String {
+        String::new()
+    }
+
+    fn is_present(&self, matches: &Matches) -> bool {
+        matches.opt_present(&self.long_name)
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+
+    fn is_valueless(&self, _: &Matches) -> bool {
+        false
+    }
+
+    fn long_usage(&self) -> String {
+        format_long_usage(&self.long_name, "", HasArg::No)
+    }
+
+    fn name(&self) -> String {
+        self.long_name.to_string()
+    }
+
+    fn parse(&self, matches: &Matches) -> Option<Vec<String>> {
+        Some(self.cap(matches.opt_count(&self.long_name)))
+    }
+
+    fn parse_config(&self, values: &[String]) -> Option<Vec<String>> {
+        if values.len() != 1 { return None; }
+        self.parse_env(&values[0])
+    }
+
+    fn parse_env(&self, value: &str) -> Option<Vec<String>> {
+        // Accept either a count or a truthy value representing a single occurrence
+        value.parse::<usize>().ok()
+            .or_else(|| parse_bool(value).map(|present| present as usize))
+            .map(|count| self.cap(count))
+    }
+
+    fn register(&self, options: &mut Options) {
+        options.optflagmulti(&self.short_name,
+            &self.long_name,
+            &self.desc);
+    }
+
+    fn short_usage(&self) -> String {
+        format_usage(&self.short_name, &self.long_name, "", HasArg::No, Occur::Multi)
+    }
+
+    fn takes_value(&self) -> bool {
+        false
+    }
+
+    fn usage_desc(&self) -> String {
+        let mut notes = vec!("repeatable".to_string());
+        if let Some(min) = self.min { notes.push(format!("min: {}", min)); }
+        if let Some(max) = self.max { notes.push(format!("max: {}", max)); }
+        format!("{} [{}]", self.desc, notes.join(", "))
+    }
 }
 
 struct Multi {
@@ -39,6 +218,7 @@ struct Multi {
     long_name: String,
     desc: String,
     hint: String,
+    delimiter: Option<String>
 }
 
 impl Multi {
@@ -51,30 +231,81 @@ impl Multi {
             long_name: long_name.to_string(),
             desc: desc.to_string(),
             hint: hint.to_string(),
+            delimiter: None
         }
     }
 }
 
 impl Opt for Multi {
+    fn annotate(&mut self, note: &str) {
+        self.desc = format!("{} {}", self.desc, note);
+    }
+
+    fn default_value(&self) -> Option<String> {
+        None
+    }
+
+    fn desc(&self) -> String {
+        self.desc.to_string()
+    }
+
     fn flag(&self) -> String {
         self.short_name.to_string()
     }
 
+    fn hint(&self) -> String {
+        self.hint.to_string()
+    }
+
+    fn is_present(&self, matches: &Matches) -> bool {
+        matches.opt_present(&self.long_name)
+    }
+
     fn is_required(&self) -> bool {
         false
     }
 
-    fn is_multi(&self) -> bool {
-        true
+    fn is_valueless(&self, _: &Matches) -> bool {
+        false
+    }
+
+    fn long_usage(&self) -> String {
+        format_long_usage(&self.long_name, &self.hint, HasArg::Yes)
     }
 
     fn name(&self) -> String {
         self.long_name.to_string()
     }
 
-    fn parse(&self, matches: &Matches) -> Option<String> {
-        let strs = matches.opt_strs(&self.long_name);
-        if strs.is_empty() { None } else { Some(strs.join(SEPARATOR)) }
+    fn parse(&self, matches: &Matches) -> Option<Vec<String>> {
+        let mut strs = matches.opt_strs(&self.long_name);
+
+        // Only split values if a delimiter has been opted into
+        if let Some(ref delimiter) = self.delimiter {
+            strs = strs.iter().flat_map(|value| value.split(delimiter.as_str()))
+                .map(|value| value.to_string())
+                .collect();
+        }
+
+        if strs.is_empty() { None } else { Some(strs) }
+    }
+
+    fn parse_config(&self, values: &[String]) -> Option<Vec<String>> {
+        match self.delimiter {
+            Some(ref delimiter) => Some(values.iter()
+                .flat_map(|value| value.split(delimiter.as_str()))
+                .map(|value| value.to_string())
+                .collect()),
+            None => Some(values.to_vec())
+        }
+    }
+
+    fn parse_env(&self, value: &str) -> Option<Vec<String>> {
+        let delimiter = self.delimiter.as_ref().map_or(ENV_DELIMITER, |delimiter| delimiter.as_str());
+        Some(value.split(delimiter)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+            .collect())
     }
 
     fn register(&self, options: &mut Options) {
@@ -83,6 +314,126 @@ impl Opt for Multi {
             &self.desc,
             &self.hint);
     }
+
+    fn short_usage(&self) -> String {
+        format_usage(&self.short_name, &self.long_name, &self.hint, HasArg::Yes, Occur::Multi)
+    }
+
+    fn takes_value(&self) -> bool {
+        true
+    }
+}
+
+struct Negatable {
+    short_name: String,
+    long_name: String,
+    desc: String,
+    default: bool
+}
+
+impl Negatable {
+    fn new(short_name: &str,
+            long_name: &str,
+            desc: &str,
+            default: bool) -> Self {
+        Negatable {
+            short_name: short_name.to_string(),
+            long_name: long_name.to_string(),
+            desc: desc.to_string(),
+            default
+        }
+    }
+
+    fn negated_name(&self) -> String {
+        format!("{}{}", NEGATION_PREFIX, self.long_name)
+    }
+}
+
+impl Opt for Negatable {
+    fn annotate(&mut self, note: &str) {
+        self.desc = format!("{} {}", self.desc, note);
+    }
+
+    fn default_value(&self) -> Option<String> {
+        Some(self.default.to_string())
+    }
+
+    fn desc(&self) -> String {
+        self.desc.to_string()
+    }
+
+    fn flag(&self) -> String {
+        self.short_name.to_string()
+    }
+
+    fn hint(&self) -> String {
+        String::new()
+    }
+
+    fn is_present(&self, matches: &Matches) -> bool {
+        matches.opt_present(&self.long_name) || matches.opt_present(&self.negated_name())
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+
+    fn is_switch(&self) -> bool {
+        true
+    }
+
+    fn is_valueless(&self, _: &Matches) -> bool {
+        false
+    }
+
+    fn long_usage(&self) -> String {
+        format!("--[{}]{}", NEGATION_PREFIX, self.long_name)
+    }
+
+    fn long_names(&self) -> Vec<String> {
+        vec!(self.name(), self.negated_name())
+    }
+
+    fn name(&self) -> String {
+        self.long_name.to_string()
+    }
+
+    fn parse(&self, matches: &Matches) -> Option<Vec<String>> {
+        // If both forms are given the last one wins
+        let enabled = matches.opt_positions(&self.long_name).into_iter().max();
+        let disabled = matches.opt_positions(&self.negated_name()).into_iter().max();
+        let value = match (enabled, disabled) {
+            (None, None) => self.default,
+            (enabled, disabled) => enabled > disabled
+        };
+        Some(vec!(value.to_string()))
+    }
+
+    fn parse_config(&self, values: &[String]) -> Option<Vec<String>> {
+        if values.len() != 1 { return None; }
+        self.parse_env(&values[0])
+    }
+
+    fn parse_env(&self, value: &str) -> Option<Vec<String>> {
+        parse_bool(value).map(|enabled| vec!(enabled.to_string()))
+    }
+
+    fn register(&self, options: &mut Options) {
+        options.optflagmulti(&self.short_name, &self.long_name, &self.desc);
+        options.optflagmulti("", &self.negated_name(), &self.desc);
+    }
+
+    fn short_usage(&self) -> String {
+        format!("[--[{}]{}]", NEGATION_PREFIX, self.long_name)
+    }
+
+    fn takes_value(&self) -> bool {
+        false
+    }
+
+    fn usage_desc(&self) -> String {
+        format!("{} [default: {}]", self.desc, self.default)
+    }
 }
 
 struct Single {
@@ -92,7 +443,8 @@ struct Single {
     hint: String,
     has_arg: HasArg,
     occur: Occur,
-    default: Option<String>
+    default: Option<String>,
+    implicit: Option<String>
 }
 
 impl Single {
@@ -111,61 +463,161 @@ impl Single {
             long_name: long_name.to_string(),
             desc: desc.to_string(),
             hint: hint.to_string(),
-            has_arg: has_arg,
-            occur: occur,
-            default: default
+            has_arg,
+            occur,
+            default,
+            implicit: None
         }
     }
 }
 
 impl Opt for Single {
+    fn annotate(&mut self, note: &str) {
+        self.desc = format!("{} {}", self.desc, note);
+    }
+
+    fn default_value(&self) -> Option<String> {
+        self.default.clone()
+    }
+
+    fn desc(&self) -> String {
+        self.desc.to_string()
+    }
+
     fn flag(&self) -> String {
         self.short_name.to_string()
     }
 
+    fn has_arg(&self) -> HasArg {
+        self.has_arg
+    }
+
+    fn hint(&self) -> String {
+        self.hint.to_string()
+    }
+
+    fn is_present(&self, matches: &Matches) -> bool {
+        matches.opt_present(&self.long_name)
+    }
+
     fn is_required(&self) -> bool {
         self.occur == Occur::Req
     }
 
-    fn is_multi(&self) -> bool {
-        false
+    fn is_switch(&self) -> bool {
+        self.has_arg == HasArg::No
+    }
+
+    fn is_valueless(&self, matches: &Matches) -> bool {
+        self.has_arg == HasArg::Maybe &&
+            matches.opt_present(&self.long_name) &&
+            matches.opt_str(&self.long_name).is_none()
+    }
+
+    fn long_usage(&self) -> String {
+        format_long_usage(&self.long_name, &self.hint, self.has_arg)
     }
 
     fn name(&self) -> String {
         self.long_name.to_string()
     }
 
-    fn parse(&self, matches: &Matches) -> Option<String> {
+    fn parse(&self, matches: &Matches) -> Option<Vec<String>> {
         // If the option does not have an argument, return presence
         if self.has_arg == HasArg::No {
-            return Some(matches.opt_present(&self.long_name).to_string());
+            return Some(vec!(matches.opt_present(&self.long_name).to_string()));
         }
 
+        // If the option was given without its optional argument, use the implicit value
+        if self.is_valueless(matches) { return self.implicit.clone().map(|implicit| vec!(implicit)); }
+
         // If the option does have an arugment, parse it or get the default
         matches.opt_str(&self.long_name).or_else(|| {
             // Return the default if it is defined and there is no match
             if self.default.is_some() { return self.default.clone(); }
             None
-        })
+        }).map(|value| vec!(value))
+    }
+
+    fn parse_config(&self, values: &[String]) -> Option<Vec<String>> {
+        // Only a single value may be assigned to a single option
+        if values.len() != 1 { return None; }
+        self.parse_env(&values[0])
+    }
+
+    fn parse_env(&self, value: &str) -> Option<Vec<String>> {
+        // If the option does not have an argument, the value must be truthy or falsy
+        if self.has_arg == HasArg::No {
+            return parse_bool(value).map(|present| vec!(present.to_string()));
+        }
+
+        Some(vec!(value.to_string()))
     }
 
     fn register(&self, options: &mut Options) {
+        // Required options are enforced by `Args` so they may be satisfied by other sources
         options.opt(&self.short_name,
             &self.long_name,
             &self.desc,
             &self.hint,
             self.has_arg,
-            self.occur);
+            Occur::Optional);
+    }
+
+    fn short_usage(&self) -> String {
+        format_usage(&self.short_name, &self.long_name, &self.hint, self.has_arg, self.occur)
+    }
+
+    fn takes_value(&self) -> bool {
+        self.has_arg == HasArg::Yes
+    }
+}
+
+// Private associated methods
+fn format_long_usage(long_name: &str, hint: &str, has_arg: HasArg) -> String {
+    match has_arg {
+        HasArg::Yes => format!("--{} {}", long_name, hint),
+        HasArg::Maybe => format!("--{} [{}]", long_name, hint),
+        HasArg::No => format!("--{}", long_name)
+    }
+}
+
+fn format_usage(short_name: &str, long_name: &str, hint: &str, has_arg: HasArg, occur: Occur) -> String {
+    // Prefer the short name, but fall back to the long name
+    let mut usage = if short_name.is_empty() {
+        format!("--{}", long_name)
+    } else {
+        format!("-{}", short_name)
+    };
+
+    match has_arg {
+        HasArg::Yes => { usage = format!("{} {}", usage, hint); },
+        HasArg::Maybe => { usage = format!("{} [{}]", usage, hint); },
+        HasArg::No => {}
+    }
+
+    match occur {
+        Occur::Req => usage,
+        Occur::Optional => format!("[{}]", usage),
+        Occur::Multi => format!("[{}]..", usage)
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "" | "0" | "false" | "no" | "off" => Some(false),
+        _ => None
     }
 }
 
-impl Display for Opt {
+impl Display for dyn Opt {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "option '-{} --{}'", self.flag(), self.name())
     }
 }
 
-impl Debug for Opt {
+impl Debug for dyn Opt {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         write!(f, "option '-{} --{}'", self.flag(), self.name())
     }
@@ -2,7 +2,40 @@ use getopts::{HasArg,Occur};
 
 use options::{self,Opt};
 
-fn create(has_arg: HasArg, occur: Occur, default: Option<String>) -> Box<Opt> {
+fn create_count(min: Option<usize>, max: Option<usize>) -> Box<dyn Opt> {
+    options::count("v",
+        "verbose",
+        "Verbose",
+        min,
+        max)
+}
+
+fn create_delimited(delimiter: &str) -> Box<dyn Opt> {
+    options::delimited("o",
+        "option",
+        "Option",
+        "OPT",
+        delimiter)
+}
+
+fn create_negatable(default: bool) -> Box<dyn Opt> {
+    options::negatable("c",
+        "color",
+        "Color",
+        default)
+}
+
+fn create_maybe(implicit: Option<String>) -> Box<dyn Opt> {
+    options::maybe("o",
+        "option",
+        "Option",
+        "OPT",
+        Occur::Optional,
+        None,
+        implicit)
+}
+
+fn create(has_arg: HasArg, occur: Occur, default: Option<String>) -> Box<dyn Opt> {
     options::new("o",
         "option",
         "Option",
@@ -53,6 +86,45 @@ mod opt {
         }
     }
 
+    mod long_usage {
+        mod when_value {
+            use getopts::{HasArg,Occur};
+            use super::super::super::create;
+
+            #[test]
+            fn returns_long_name_and_hint() {
+                assert_eq!("--option OPT".to_string(), create(HasArg::Yes, Occur::Req, None).long_usage());
+            }
+        }
+
+        mod when_optional_value {
+            use super::super::super::create_maybe;
+
+            #[test]
+            fn returns_bracketed_hint() {
+                assert_eq!("--option [OPT]".to_string(), create_maybe(None).long_usage());
+            }
+        }
+
+        mod when_flag {
+            use super::super::super::create_count;
+
+            #[test]
+            fn returns_long_name() {
+                assert_eq!("--verbose".to_string(), create_count(None, None).long_usage());
+            }
+        }
+
+        mod when_negatable {
+            use super::super::super::create_negatable;
+
+            #[test]
+            fn returns_both_forms() {
+                assert_eq!("--[no-]color".to_string(), create_negatable(true).long_usage());
+            }
+        }
+    }
+
     mod parse {
         mod flag {
             mod absent {
@@ -68,7 +140,7 @@ mod opt {
 
                     let parsed = opt.parse(&matches);
                     assert!(parsed.is_some());
-                    assert_eq!("false".to_string(), parsed.unwrap());
+                    assert_eq!(vec!("false".to_string()), parsed.unwrap());
                 }
             }
 
@@ -85,7 +157,206 @@ mod opt {
 
                     let parsed = opt.parse(&matches);
                     assert!(parsed.is_some());
-                    assert_eq!("true".to_string(), parsed.unwrap());
+                    assert_eq!(vec!("true".to_string()), parsed.unwrap());
+                }
+            }
+        }
+
+        mod maybe {
+            mod absent {
+                use getopts::Options;
+                use super::super::super::super::create_maybe;
+
+                #[test]
+                fn returns_none() {
+                    let mut options = Options::new();
+                    let opt = create_maybe(Some("implicit".to_string()));
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("")).unwrap();
+
+                    assert!(!opt.is_valueless(&matches));
+                    assert!(opt.parse(&matches).is_none());
+                }
+            }
+
+            mod valueless {
+                mod with_implicit {
+                    use getopts::Options;
+                    use super::super::super::super::super::create_maybe;
+
+                    #[test]
+                    fn returns_some_implicit() {
+                        let mut options = Options::new();
+                        let opt = create_maybe(Some("implicit".to_string()));
+                        opt.register(&mut options);
+                        let matches = options.parse(vec!("--option")).unwrap();
+
+                        assert!(opt.is_valueless(&matches));
+                        assert_eq!(Some(vec!("implicit".to_string())), opt.parse(&matches));
+                    }
+                }
+
+                mod without_implicit {
+                    use getopts::Options;
+                    use super::super::super::super::super::create_maybe;
+
+                    #[test]
+                    fn returns_none() {
+                        let mut options = Options::new();
+                        let opt = create_maybe(None);
+                        opt.register(&mut options);
+                        let matches = options.parse(vec!("--option")).unwrap();
+
+                        assert!(opt.is_valueless(&matches));
+                        assert!(opt.parse(&matches).is_none());
+                    }
+                }
+            }
+
+            mod present {
+                use getopts::Options;
+                use super::super::super::super::create_maybe;
+
+                #[test]
+                fn returns_some_value() {
+                    let mut options = Options::new();
+                    let opt = create_maybe(Some("implicit".to_string()));
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("--option=value")).unwrap();
+
+                    assert!(!opt.is_valueless(&matches));
+                    assert_eq!(Some(vec!("value".to_string())), opt.parse(&matches));
+                }
+            }
+        }
+
+        mod count {
+            mod uncapped {
+                use getopts::Options;
+                use super::super::super::super::create_count;
+
+                #[test]
+                fn returns_some_occurrences() {
+                    let mut options = Options::new();
+                    let opt = create_count(None, None);
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("-vv", "--verbose")).unwrap();
+
+                    assert_eq!(Some(vec!("3".to_string())), opt.parse(&matches));
+                }
+            }
+
+            mod below_min {
+                use getopts::Options;
+                use super::super::super::super::create_count;
+
+                #[test]
+                fn returns_some_min() {
+                    let mut options = Options::new();
+                    let opt = create_count(Some(1), None);
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("")).unwrap();
+
+                    assert_eq!(Some(vec!("1".to_string())), opt.parse(&matches));
+                }
+            }
+
+            mod above_max {
+                use getopts::Options;
+                use super::super::super::super::create_count;
+
+                #[test]
+                fn returns_some_max() {
+                    let mut options = Options::new();
+                    let opt = create_count(None, Some(2));
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("-vvvv")).unwrap();
+
+                    assert_eq!(Some(vec!("2".to_string())), opt.parse(&matches));
+                }
+            }
+        }
+
+        mod negatable {
+            mod absent {
+                use getopts::Options;
+                use super::super::super::super::create_negatable;
+
+                #[test]
+                fn returns_some_default() {
+                    let mut options = Options::new();
+                    let opt = create_negatable(true);
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("")).unwrap();
+
+                    assert!(!opt.is_present(&matches));
+                    assert_eq!(Some(vec!("true".to_string())), opt.parse(&matches));
+                }
+            }
+
+            mod negated {
+                use getopts::Options;
+                use super::super::super::super::create_negatable;
+
+                #[test]
+                fn returns_some_false() {
+                    let mut options = Options::new();
+                    let opt = create_negatable(true);
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("--no-color")).unwrap();
+
+                    assert!(opt.is_present(&matches));
+                    assert_eq!(Some(vec!("false".to_string())), opt.parse(&matches));
+                }
+            }
+
+            mod both {
+                use getopts::Options;
+                use super::super::super::super::create_negatable;
+
+                #[test]
+                fn returns_some_last() {
+                    let mut options = Options::new();
+                    let opt = create_negatable(false);
+                    opt.register(&mut options);
+                    let enabled = options.parse(vec!("--no-color", "-c")).unwrap();
+                    let disabled = options.parse(vec!("--color", "--no-color")).unwrap();
+
+                    assert_eq!(Some(vec!("true".to_string())), opt.parse(&enabled));
+                    assert_eq!(Some(vec!("false".to_string())), opt.parse(&disabled));
+                }
+            }
+        }
+
+        mod multi {
+            mod not_delimited {
+                use getopts::{HasArg,Occur,Options};
+                use super::super::super::super::create;
+
+                #[test]
+                fn returns_each_occurrence() {
+                    let mut options = Options::new();
+                    let opt = create(HasArg::Yes, Occur::Multi, None);
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("-o", "a,b", "-o", "c")).unwrap();
+
+                    assert_eq!(Some(vec!("a,b".to_string(), "c".to_string())), opt.parse(&matches));
+                }
+            }
+
+            mod delimited {
+                use getopts::Options;
+                use super::super::super::super::create_delimited;
+
+                #[test]
+                fn returns_split_values() {
+                    let mut options = Options::new();
+                    let opt = create_delimited(",");
+                    opt.register(&mut options);
+                    let matches = options.parse(vec!("-o", "a,b", "-o", "c")).unwrap();
+
+                    assert_eq!(Some(vec!("a".to_string(), "b".to_string(), "c".to_string())),
+                        opt.parse(&matches));
                 }
             }
         }
@@ -122,7 +393,7 @@ mod opt {
 
                         let parsed = opt.parse(&matches);
                         assert!(parsed.is_some());
-                        assert_eq!(default.to_string(), parsed.unwrap());
+                        assert_eq!(vec!(default.to_string()), parsed.unwrap());
                     }
                 }
             }
@@ -141,9 +412,147 @@ mod opt {
 
                     let parsed = opt.parse(&matches);
                     assert!(parsed.is_some());
-                    assert_eq!(value.to_string(), parsed.unwrap());
+                    assert_eq!(vec!(value.to_string()), parsed.unwrap());
+                }
+            }
+        }
+    }
+
+    mod parse_env {
+        mod flag {
+            mod truthy {
+                use getopts::{HasArg,Occur};
+                use super::super::super::super::create;
+
+                #[test]
+                fn returns_some_true() {
+                    let opt = create(HasArg::No, Occur::Optional, None);
+
+                    assert_eq!(Some(vec!("true".to_string())), opt.parse_env("yes"));
+                }
+            }
+
+            mod falsy {
+                use getopts::{HasArg,Occur};
+                use super::super::super::super::create;
+
+                #[test]
+                fn returns_some_false() {
+                    let opt = create(HasArg::No, Occur::Optional, None);
+
+                    assert_eq!(Some(vec!("false".to_string())), opt.parse_env("0"));
+                }
+            }
+
+            mod invalid {
+                use getopts::{HasArg,Occur};
+                use super::super::super::super::create;
+
+                #[test]
+                fn returns_none() {
+                    let opt = create(HasArg::No, Occur::Optional, None);
+
+                    assert!(opt.parse_env("maybe").is_none());
+                }
+            }
+        }
+
+        mod count {
+            use super::super::super::create_count;
+
+            #[test]
+            fn accepts_counts_and_truthy_values() {
+                let opt = create_count(None, Some(3));
+
+                assert_eq!(Some(vec!("2".to_string())), opt.parse_env("2"));
+                assert_eq!(Some(vec!("3".to_string())), opt.parse_env("5"));
+                assert_eq!(Some(vec!("1".to_string())), opt.parse_env("true"));
+                assert!(opt.parse_env("loud").is_none());
+            }
+        }
+
+        mod multi {
+            mod not_delimited {
+                use getopts::{HasArg,Occur};
+                use super::super::super::super::create;
+
+                #[test]
+                fn returns_comma_split_values() {
+                    let opt = create(HasArg::Yes, Occur::Multi, None);
+
+                    assert_eq!(Some(vec!("a".to_string(), "b".to_string())), opt.parse_env("a,b"));
+                }
+            }
+
+            mod delimited {
+                use super::super::super::super::create_delimited;
+
+                #[test]
+                fn returns_split_values() {
+                    let opt = create_delimited(":");
+
+                    assert_eq!(Some(vec!("a,b".to_string(), "c".to_string())), opt.parse_env("a,b:c"));
                 }
             }
         }
     }
+
+    mod short_usage {
+        mod when_required {
+            use getopts::{HasArg,Occur};
+            use super::super::super::create;
+
+            #[test]
+            fn returns_unbracketed() {
+                assert_eq!("-o OPT".to_string(), create(HasArg::Yes, Occur::Req, None).short_usage());
+            }
+        }
+
+        mod when_optional_value {
+            use super::super::super::create_maybe;
+
+            #[test]
+            fn returns_bracketed_hint() {
+                assert_eq!("[-o [OPT]]".to_string(), create_maybe(None).short_usage());
+            }
+        }
+
+        mod when_repeated_optional_value {
+            use getopts::{HasArg,Occur};
+            use super::super::super::create;
+
+            #[test]
+            fn returns_single_occurrence() {
+                assert_eq!("[-o [OPT]]".to_string(), create(HasArg::Maybe, Occur::Multi, None).short_usage());
+            }
+        }
+
+        mod when_count {
+            use super::super::super::create_count;
+
+            #[test]
+            fn returns_repeated_flag() {
+                assert_eq!("[-v]..".to_string(), create_count(None, None).short_usage());
+            }
+        }
+
+        mod when_negatable {
+            use super::super::super::create_negatable;
+
+            #[test]
+            fn returns_both_forms() {
+                assert_eq!("[--[no-]color]".to_string(), create_negatable(true).short_usage());
+            }
+        }
+
+        mod when_multi {
+            use getopts::{HasArg,Occur};
+            use super::super::super::create;
+
+            #[test]
+            fn returns_repeated() {
+                assert_eq!("[-o OPT]..".to_string(), create(HasArg::Yes, Occur::Multi, None).short_usage());
+            }
+        }
+    }
 }
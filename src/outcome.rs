@@ -0,0 +1,37 @@
+use super::report::{self,Stream,EX_OK};
+
+/// The result of a successful parse.
+#[derive(Clone,Debug,PartialEq)]
+pub enum ParseOutcome {
+    /// The arguments were parsed and their values may be retrieved.
+    Parsed,
+    /// Help was requested, carrying the usage message to print.
+    Help(String),
+    /// The version was requested, carrying the version message to print.
+    Version(String)
+}
+
+impl ParseOutcome {
+    /// Returns a `bool` indicating whether or not the arguments were parsed, as opposed
+    /// to help or the version being requested.
+    pub fn is_parsed(&self) -> bool {
+        matches!(*self, ParseOutcome::Parsed)
+    }
+
+    /// Writes the help or version message, if any, to standard output and exits the
+    /// process successfully.
+    pub fn exit(&self) -> ! {
+        let (code, stream, text) = self.report();
+        report::exit(code, stream, &text)
+    }
+
+    /// Returns the exit code, stream and text which `exit` uses, without exiting. The
+    /// text is empty if the arguments were parsed.
+    pub fn report(&self) -> (i32, Stream, String) {
+        let text = match *self {
+            ParseOutcome::Parsed => "",
+            ParseOutcome::Help(ref text) | ParseOutcome::Version(ref text) => text.trim_end_matches('\n')
+        };
+        (EX_OK, Stream::Stdout, text.to_string())
+    }
+}
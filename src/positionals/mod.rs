@@ -0,0 +1,64 @@
+use getopts::Occur;
+use std::mem;
+
+#[cfg(test)] mod tst;
+
+pub struct Positional {
+    name: String,
+    desc: String,
+    hint: String,
+    occur: Occur
+}
+
+impl Positional {
+    pub fn new(name: &str, desc: &str, hint: &str, occur: Occur) -> Self {
+        // If there is no hint, fall back to the name
+        let hint = if hint.is_empty() { name.to_uppercase() } else { hint.to_string() };
+
+        Positional {
+            name: name.to_string(),
+            desc: desc.to_string(),
+            hint,
+            occur
+        }
+    }
+
+    pub fn desc(&self) -> String {
+        self.desc.to_string()
+    }
+
+    pub fn hint(&self) -> String {
+        self.hint.to_string()
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.occur == Occur::Req
+    }
+
+    pub fn is_variadic(&self) -> bool {
+        self.occur == Occur::Multi
+    }
+
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    /// Takes the values belonging to this positional from the front of `free`.
+    pub fn parse(&self, free: &mut Vec<String>) -> Option<Vec<String>> {
+        if free.is_empty() { return None; }
+
+        if self.is_variadic() {
+            Some(mem::take(free))
+        } else {
+            Some(vec!(free.remove(0)))
+        }
+    }
+
+    pub fn short_usage(&self) -> String {
+        match self.occur {
+            Occur::Req => self.hint.to_string(),
+            Occur::Optional => format!("[{}]", self.hint),
+            Occur::Multi => format!("[{}]..", self.hint)
+        }
+    }
+}
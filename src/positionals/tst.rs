@@ -0,0 +1,97 @@
+use getopts::Occur;
+
+use positionals::Positional;
+
+fn create(occur: Occur) -> Positional {
+    Positional::new("file", "File", "FILE", occur)
+}
+
+mod positional {
+    mod hint {
+        mod without_hint {
+            use getopts::Occur;
+            use positionals::Positional;
+
+            #[test]
+            fn returns_uppercase_name() {
+                let positional = Positional::new("file", "File", "", Occur::Req);
+
+                assert_eq!("FILE".to_string(), positional.hint());
+            }
+        }
+    }
+
+    mod parse {
+        mod absent {
+            use getopts::Occur;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_none() {
+                let mut free: Vec<String> = Vec::new();
+
+                assert!(create(Occur::Req).parse(&mut free).is_none());
+            }
+        }
+
+        mod single {
+            use getopts::Occur;
+            use super::super::super::create;
+
+            #[test]
+            fn takes_first_value() {
+                let mut free = vec!("a".to_string(), "b".to_string());
+
+                let parsed = create(Occur::Optional).parse(&mut free);
+                assert_eq!(Some(vec!("a".to_string())), parsed);
+                assert_eq!(vec!("b".to_string()), free);
+            }
+        }
+
+        mod variadic {
+            use getopts::Occur;
+            use super::super::super::create;
+
+            #[test]
+            fn takes_all_values() {
+                let mut free = vec!("a".to_string(), "b".to_string());
+
+                let parsed = create(Occur::Multi).parse(&mut free);
+                assert_eq!(Some(vec!("a".to_string(), "b".to_string())), parsed);
+                assert!(free.is_empty());
+            }
+        }
+    }
+
+    mod short_usage {
+        mod when_required {
+            use getopts::Occur;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_hint() {
+                assert_eq!("FILE".to_string(), create(Occur::Req).short_usage());
+            }
+        }
+
+        mod when_optional {
+            use getopts::Occur;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_bracketed_hint() {
+                assert_eq!("[FILE]".to_string(), create(Occur::Optional).short_usage());
+            }
+        }
+
+        mod when_variadic {
+            use getopts::Occur;
+            use super::super::super::create;
+
+            #[test]
+            fn returns_repeated_hint() {
+                assert_eq!("[FILE]..".to_string(), create(Occur::Multi).short_usage());
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use getopts::HasArg;
+
+#[cfg(test)] mod tst;
+
+const END_OF_OPTIONS: &str = "--";
+
+/// Returns the indices of the arguments in `args` in which the option `name` occurs,
+/// along with the byte offset of short options within their cluster. Arguments are
+/// walked as getopts does, using `has_arg` to skip values, including the rest of a
+/// cluster after a short option which takes a value, and stopping at `--`.
+pub fn occurrences<A>(args: &[String], name: &str, has_arg: A) -> Vec<(usize, Option<usize>)>
+        where A: Fn(&str) -> HasArg {
+    let mut occurrences = Vec::new();
+    let mut index = 0;
+    while index < args.len() {
+        let arg = &args[index];
+        let mut consumes_next = None;
+
+        if arg == END_OF_OPTIONS {
+            break;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (long, attached) = match long.find('=') {
+                Some(end) => (&long[..end], true),
+                None => (long, false)
+            };
+            if long == name { occurrences.push((index, None)); }
+            if !attached { consumes_next = Some(has_arg(long)); }
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            for (offset, c) in arg.char_indices().skip(1) {
+                let short = c.to_string();
+                if short == name { occurrences.push((index, Some(offset))); }
+
+                // A short option which may take a value takes the rest of the cluster
+                let takes = has_arg(&short);
+                if takes != HasArg::No {
+                    if offset + c.len_utf8() == arg.len() { consumes_next = Some(takes); }
+                    break;
+                }
+            }
+        }
+
+        index += 1;
+        match consumes_next {
+            Some(HasArg::Yes) if index < args.len() => { index += 1; },
+            Some(HasArg::Maybe) if args.get(index).is_some_and(|next| !next.starts_with('-')) => { index += 1; },
+            _ => {}
+        }
+    }
+    occurrences
+}
+
+/// Returns the index of an occurrence of the option `name` in `args`, see `occurrences`.
+/// The last occurrence is found if `from_end` is set, otherwise the first.
+pub fn find<A>(args: &[String], name: &str, from_end: bool, has_arg: A) -> Option<usize> where A: Fn(&str) -> HasArg {
+    let occurrences = occurrences(args, name, has_arg);
+    let occurrence = if from_end { occurrences.last() } else { occurrences.first() };
+    occurrence.map(|&(index, _)| index)
+}
+
+/// Removes the option `name` from the argument at `index` so that parsing may be retried
+/// after it was rejected. Long options are removed along with any attached value, while
+/// short options are removed from their cluster. Returns `true` if the whole argument
+/// was removed.
+pub fn remove<A>(args: &mut Vec<String>, index: usize, name: &str, has_arg: A) -> bool where A: Fn(&str) -> HasArg {
+    let offset = occurrences(args, name, has_arg).into_iter()
+        .find(|&(occurrence, _)| occurrence == index)
+        .and_then(|(_, offset)| offset);
+    if let Some(offset) = offset {
+        let mut cluster = args[index].to_string();
+        cluster.remove(offset);
+        if cluster != "-" {
+            args[index] = cluster;
+            return false;
+        }
+    }
+
+    args.remove(index);
+    true
+}
@@ -0,0 +1,132 @@
+use getopts::HasArg;
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+// `o` and `output` take a value, `c` and `color` may take one
+fn has_arg(name: &str) -> HasArg {
+    match name {
+        "o" | "output" => HasArg::Yes,
+        "c" | "color" => HasArg::Maybe,
+        _ => HasArg::No
+    }
+}
+
+mod find {
+    mod long {
+        use super::super::{has_arg,strings};
+        use recovery::find;
+
+        #[test]
+        fn finds_option() {
+            assert_eq!(Some(1), find(&strings(&["-a", "--bad"]), "bad", false, has_arg));
+        }
+
+        #[test]
+        fn finds_attached_value() {
+            assert_eq!(Some(0), find(&strings(&["--bad=value"]), "bad", false, has_arg));
+        }
+
+        #[test]
+        fn ignores_longer_names() {
+            assert_eq!(None, find(&strings(&["--badge"]), "bad", false, has_arg));
+        }
+
+        #[test]
+        fn finds_last_occurrence_from_end() {
+            assert_eq!(Some(1), find(&strings(&["--opt=1", "--opt=2"]), "opt", true, has_arg));
+        }
+
+        #[test]
+        fn finds_first_occurrence_otherwise() {
+            assert_eq!(Some(0), find(&strings(&["--opt=1", "--opt=2"]), "opt", false, has_arg));
+        }
+
+        #[test]
+        fn skips_values() {
+            assert_eq!(None, find(&strings(&["--output", "--bad"]), "bad", false, has_arg));
+        }
+    }
+
+    mod short {
+        use super::super::{has_arg,strings};
+        use recovery::find;
+
+        #[test]
+        fn finds_option_in_cluster() {
+            assert_eq!(Some(1), find(&strings(&["-a", "-bxc"]), "x", false, has_arg));
+        }
+
+        #[test]
+        fn ignores_long_options() {
+            assert_eq!(None, find(&strings(&["--xyz"]), "x", false, has_arg));
+        }
+
+        #[test]
+        fn skips_attached_values() {
+            assert_eq!(None, find(&strings(&["-ofoo"]), "f", false, has_arg));
+        }
+
+        #[test]
+        fn skips_separate_values() {
+            assert_eq!(None, find(&strings(&["-o", "-f"]), "f", false, has_arg));
+        }
+
+        #[test]
+        fn skips_optional_values() {
+            assert_eq!(None, find(&strings(&["-c", "f"]), "f", false, has_arg));
+            assert_eq!(Some(1), find(&strings(&["-c", "-f"]), "f", false, has_arg));
+        }
+    }
+
+    mod end_of_options {
+        use super::super::{has_arg,strings};
+        use recovery::find;
+
+        #[test]
+        fn ignores_following_arguments() {
+            assert_eq!(None, find(&strings(&["--", "--bad"]), "bad", false, has_arg));
+        }
+    }
+}
+
+mod remove {
+    mod long {
+        use super::super::{has_arg,strings};
+        use recovery::remove;
+
+        #[test]
+        fn removes_argument() {
+            let mut args = strings(&["--bad=value", "-a"]);
+            assert!(remove(&mut args, 0, "bad", has_arg));
+            assert_eq!(vec!("-a"), args);
+        }
+    }
+
+    mod short {
+        use super::super::{has_arg,strings};
+        use recovery::remove;
+
+        #[test]
+        fn removes_from_cluster() {
+            let mut args = strings(&["-axb"]);
+            assert!(!remove(&mut args, 0, "x", has_arg));
+            assert_eq!(vec!("-ab"), args);
+        }
+
+        #[test]
+        fn keeps_attached_values() {
+            let mut args = strings(&["-fofoo"]);
+            assert!(!remove(&mut args, 0, "f", has_arg));
+            assert_eq!(vec!("-ofoo"), args);
+        }
+
+        #[test]
+        fn removes_lone_option() {
+            let mut args = strings(&["-x", "-a"]);
+            assert!(remove(&mut args, 0, "x", has_arg));
+            assert_eq!(vec!("-a"), args);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{kinds,Page};
+
+/// Renders `page` as a standalone HTML document with an anchor per option, argument and command.
+pub fn render(page: &Page) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(&page.name)));
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape(&page.name)));
+    for paragraph in page.desc.split("\n\n").filter(|paragraph| !paragraph.trim().is_empty()) {
+        html.push_str(&format!("<p>{}</p>\n", escape(paragraph.trim())));
+    }
+    html.push_str("<h2>Usage</h2>\n");
+    html.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&page.synopsis)));
+
+    for (title, kind, entries) in kinds(page) {
+        if entries.is_empty() { continue; }
+
+        html.push_str(&format!("<h2>{}</h2>\n<dl>\n", title));
+        for entry in entries {
+            let anchor = entry.anchor(kind);
+            html.push_str(&format!("<dt id=\"{}\"><a href=\"#{}\"><code>{}</code></a></dt>\n",
+                anchor, anchor, escape(&entry.term())));
+            html.push_str(&format!("<dd>\n<p>{}</p>\n", escape(&entry.desc)));
+            if entry.required { html.push_str("<p>Required: yes</p>\n"); }
+            if let Some(ref default) = entry.default {
+                html.push_str(&format!("<p>Default: <code>{}</code></p>\n", escape(default)));
+            }
+            html.push_str("</dd>\n");
+        }
+        html.push_str("</dl>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// Private associated methods
+fn escape(string: &str) -> String {
+    string.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
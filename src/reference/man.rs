@@ -0,0 +1,63 @@
+use super::{kinds,Entry,Page};
+
+/// Renders `page` as a roff man page in section 1.
+pub fn render(page: &Page) -> String {
+    let name = page.name.replace(' ', "-");
+    let summary = page.desc.lines().next().unwrap_or("");
+
+    let mut roff = format!(".TH {} 1\n", escape(&name.to_uppercase()));
+    roff.push_str(".SH NAME\n");
+    roff.push_str(&format!("{} \\- {}\n", escape(&name), escape(summary)));
+    roff.push_str(".SH SYNOPSIS\n");
+    roff.push_str(&format!(".B {}\n", escape(&page.name)));
+    let rest = page.synopsis.trim_start_matches(page.name.as_str()).trim();
+    if !rest.is_empty() { roff.push_str(&format!("{}\n", escape(rest))); }
+    roff.push_str(".SH DESCRIPTION\n");
+    roff.push_str(&paragraphs(&page.desc));
+
+    for (title, _, entries) in kinds(page) {
+        if entries.is_empty() { continue; }
+
+        roff.push_str(&format!(".SH {}\n", title.to_uppercase()));
+        for entry in entries { roff.push_str(&item(entry)); }
+    }
+
+    for (title, body) in &page.sections {
+        roff.push_str(&format!(".SH {}\n", escape(&title.to_uppercase())));
+        roff.push_str(&paragraphs(body));
+    }
+    roff
+}
+
+// Private associated methods
+fn escape(string: &str) -> String {
+    let escaped = string.replace('\\', "\\e").replace('-', "\\-");
+
+    // Lines starting with a control character would be treated as requests
+    if escaped.starts_with('.') || escaped.starts_with('\'') { format!("\\&{}", escaped) } else { escaped }
+}
+
+fn item(entry: &Entry) -> String {
+    let mut term = entry.names.iter()
+        .map(|name| format!("\\fB{}\\fR", escape(name)))
+        .collect::<Vec<String>>()
+        .join(", ");
+    if !entry.hint.is_empty() {
+        if !term.is_empty() { term.push(' '); }
+        term.push_str(&format!("\\fI{}\\fR", escape(&entry.hint)));
+    }
+    format!(".TP\n{}\n{}\n", term, escape(&entry.annotated_desc()))
+}
+
+fn paragraphs(body: &str) -> String {
+    let mut roff = String::new();
+    for line in body.lines() {
+        if line.trim().is_empty() {
+            roff.push_str(".PP\n");
+        } else {
+            roff.push_str(&escape(line));
+            roff.push('\n');
+        }
+    }
+    roff
+}
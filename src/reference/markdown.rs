@@ -0,0 +1,29 @@
+use super::{kinds,Page};
+
+/// Renders `page` as Markdown with an anchor per option, argument and command.
+pub fn render(page: &Page) -> String {
+    let mut markdown = format!("# {}\n\n", page.name);
+    markdown.push_str(&format!("{}\n\n", page.desc.trim_end()));
+    markdown.push_str("## Usage\n\n");
+    markdown.push_str(&format!("```text\n{}\n```\n", page.synopsis));
+
+    for (title, kind, entries) in kinds(page) {
+        if entries.is_empty() { continue; }
+
+        markdown.push_str(&format!("\n## {}\n", title));
+        for entry in entries {
+            markdown.push_str(&format!("\n<a id=\"{}\"></a>\n", entry.anchor(kind)));
+            markdown.push_str(&format!("### `{}`\n\n", entry.term()));
+            markdown.push_str(&format!("{}\n", escape(&entry.desc)));
+            if entry.required || entry.default.is_some() { markdown.push('\n'); }
+            if entry.required { markdown.push_str("- Required: yes\n"); }
+            if let Some(ref default) = entry.default { markdown.push_str(&format!("- Default: `{}`\n", default)); }
+        }
+    }
+    markdown
+}
+
+// Private associated methods
+fn escape(string: &str) -> String {
+    string.replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,77 @@
+pub mod html;
+pub mod man;
+pub mod markdown;
+
+#[cfg(test)] mod tst;
+
+/// A program, or subcommand, as seen by the reference documentation generators.
+pub struct Page {
+    /// The name of the program, including any parent commands.
+    pub name: String,
+    /// The description of the program.
+    pub desc: String,
+    /// The short usage of the program, without the leading `Usage: `.
+    pub synopsis: String,
+    /// The options accepted by the program.
+    pub options: Vec<Entry>,
+    /// The positional arguments accepted by the program.
+    pub arguments: Vec<Entry>,
+    /// The subcommands of the program.
+    pub commands: Vec<Entry>,
+    /// Any additional sections, as pairs of title and body.
+    pub sections: Vec<(String, String)>
+}
+
+/// An option, positional argument or subcommand within a `Page`.
+pub struct Entry {
+    /// The name the entry is registered under, used for anchors.
+    pub id: String,
+    /// The names the entry may be given as, e.g. `-o` and `--output`.
+    pub names: Vec<String>,
+    /// The hint for the value of the entry, which may be empty.
+    pub hint: String,
+    /// The description of the entry.
+    pub desc: String,
+    /// The value used if the entry is not given.
+    pub default: Option<String>,
+    /// Whether or not the entry must be given.
+    pub required: bool
+}
+
+impl Entry {
+    /// Returns the description followed by any required or default annotation.
+    pub fn annotated_desc(&self) -> String {
+        let mut desc = self.desc.to_string();
+        if self.required { desc.push_str(" [required]"); }
+        if let Some(ref default) = self.default { desc.push_str(&format!(" [default: {}]", default)); }
+        desc
+    }
+
+    /// Returns a stable anchor for the entry, e.g. `option-iter` for `kind` `option`.
+    pub fn anchor(&self, kind: &str) -> String {
+        let id = self.id.to_lowercase().chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect::<String>();
+        format!("{}-{}", kind, id)
+    }
+
+    /// Returns the names of the entry followed by its hint, e.g. `-o, --output FILE`.
+    pub fn term(&self) -> String {
+        let mut term = self.names.join(", ");
+        if !self.hint.is_empty() {
+            if !term.is_empty() { term.push(' '); }
+            term.push_str(&self.hint);
+        }
+        term
+    }
+}
+
+/// The kinds of entries on a `Page`, in the order they are rendered, along with
+/// their heading and the prefix used for their anchors.
+pub fn kinds(page: &Page) -> Vec<(&'static str, &'static str, &[Entry])> {
+    vec!(
+        ("Options", "option", &page.options),
+        ("Arguments", "argument", &page.arguments),
+        ("Commands", "command", &page.commands)
+    )
+}
@@ -0,0 +1,189 @@
+use reference::{Entry,Page};
+
+fn page() -> Page {
+    Page {
+        name: "prog build".to_string(),
+        desc: "Build the project\n\nCompiles every target.".to_string(),
+        synopsis: "prog build [-r] [-o FILE] [--[no-]color] TARGET".to_string(),
+        options: vec!(
+            Entry {
+                id: "release".to_string(),
+                names: vec!("-r".to_string(), "--release".to_string()),
+                hint: String::new(),
+                desc: "Build in release mode".to_string(),
+                default: None,
+                required: false
+            },
+            Entry {
+                id: "output".to_string(),
+                names: vec!("-o".to_string(), "--output".to_string()),
+                hint: "FILE".to_string(),
+                desc: "Output file".to_string(),
+                default: Some("out.bin".to_string()),
+                required: false
+            },
+            Entry {
+                id: "color".to_string(),
+                names: vec!("--color".to_string(), "--no-color".to_string()),
+                hint: String::new(),
+                desc: "Colorize output".to_string(),
+                default: Some("true".to_string()),
+                required: false
+            }
+        ),
+        arguments: vec!(Entry {
+            id: "target".to_string(),
+            names: Vec::new(),
+            hint: "TARGET".to_string(),
+            desc: "The target to build".to_string(),
+            default: None,
+            required: true
+        }),
+        commands: Vec::new(),
+        sections: vec!(("Examples".to_string(), ".hidden files are skipped\n\nprog build -r app".to_string()))
+    }
+}
+
+mod man {
+    mod render {
+        use reference::man;
+        use super::super::page;
+
+        #[test]
+        fn matches_snapshot() {
+            assert_eq!(r#".TH PROG\-BUILD 1
+.SH NAME
+prog\-build \- Build the project
+.SH SYNOPSIS
+.B prog build
+[\-r] [\-o FILE] [\-\-[no\-]color] TARGET
+.SH DESCRIPTION
+Build the project
+.PP
+Compiles every target.
+.SH OPTIONS
+.TP
+\fB\-r\fR, \fB\-\-release\fR
+Build in release mode
+.TP
+\fB\-o\fR, \fB\-\-output\fR \fIFILE\fR
+Output file [default: out.bin]
+.TP
+\fB\-\-color\fR, \fB\-\-no\-color\fR
+Colorize output [default: true]
+.SH ARGUMENTS
+.TP
+\fITARGET\fR
+The target to build [required]
+.SH EXAMPLES
+\&.hidden files are skipped
+.PP
+prog build \-r app
+"#, man::render(&page()));
+        }
+    }
+}
+
+mod markdown {
+    mod render {
+        use reference::markdown;
+        use super::super::page;
+
+        #[test]
+        fn matches_snapshot() {
+            assert_eq!(r#"# prog build
+
+Build the project
+
+Compiles every target.
+
+## Usage
+
+```text
+prog build [-r] [-o FILE] [--[no-]color] TARGET
+```
+
+## Options
+
+<a id="option-release"></a>
+### `-r, --release`
+
+Build in release mode
+
+<a id="option-output"></a>
+### `-o, --output FILE`
+
+Output file
+
+- Default: `out.bin`
+
+<a id="option-color"></a>
+### `--color, --no-color`
+
+Colorize output
+
+- Default: `true`
+
+## Arguments
+
+<a id="argument-target"></a>
+### `TARGET`
+
+The target to build
+
+- Required: yes
+"#, markdown::render(&page()));
+        }
+    }
+}
+
+mod html {
+    mod render {
+        use reference::html;
+        use super::super::page;
+
+        #[test]
+        fn matches_snapshot() {
+            assert_eq!(r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>prog build</title>
+</head>
+<body>
+<h1>prog build</h1>
+<p>Build the project</p>
+<p>Compiles every target.</p>
+<h2>Usage</h2>
+<pre><code>prog build [-r] [-o FILE] [--[no-]color] TARGET</code></pre>
+<h2>Options</h2>
+<dl>
+<dt id="option-release"><a href="#option-release"><code>-r, --release</code></a></dt>
+<dd>
+<p>Build in release mode</p>
+</dd>
+<dt id="option-output"><a href="#option-output"><code>-o, --output FILE</code></a></dt>
+<dd>
+<p>Output file</p>
+<p>Default: <code>out.bin</code></p>
+</dd>
+<dt id="option-color"><a href="#option-color"><code>--color, --no-color</code></a></dt>
+<dd>
+<p>Colorize output</p>
+<p>Default: <code>true</code></p>
+</dd>
+</dl>
+<h2>Arguments</h2>
+<dl>
+<dt id="argument-target"><a href="#argument-target"><code>TARGET</code></a></dt>
+<dd>
+<p>The target to build</p>
+<p>Required: yes</p>
+</dd>
+</dl>
+</body>
+</html>
+"##, html::render(&page()));
+        }
+    }
+}
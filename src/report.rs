@@ -0,0 +1,30 @@
+use std::io::{self,Write};
+use std::process;
+
+/// The exit code for a successful run, e.g. after printing help.
+pub const EX_OK: i32 = 0;
+/// The exit code for a command used incorrectly, as in `sysexits.h`.
+pub const EX_USAGE: i32 = 64;
+/// The exit code for an invalid configuration, as in `sysexits.h`.
+pub const EX_CONFIG: i32 = 78;
+
+/// The standard stream a message is written to before exiting.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Stream {
+    /// Standard output, used for help and version messages.
+    Stdout,
+    /// Standard error, used for errors.
+    Stderr
+}
+
+/// Writes `text`, if any, followed by a newline to `stream` and exits the process with `code`.
+pub fn exit(code: i32, stream: Stream, text: &str) -> ! {
+    // Failures to write, e.g. to a closed pipe, are ignored as the process exits regardless
+    if !text.is_empty() {
+        let _ = match stream {
+            Stream::Stdout => writeln!(io::stdout(), "{}", text).and_then(|_| io::stdout().flush()),
+            Stream::Stderr => writeln!(io::stderr(), "{}", text)
+        };
+    }
+    process::exit(code)
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::{Path,PathBuf};
+
+use super::{ArgsError,ErrorKind,SCOPE_PARSE};
+
+#[cfg(test)] mod tst;
+
+const END_OF_OPTIONS: &str = "--";
+const PREFIX: char = '@';
+
+/// An argument after expansion.
+#[derive(Debug)]
+pub struct Expanded {
+    /// The argument itself.
+    pub arg: String,
+    /// The index of the given argument it was expanded from.
+    pub index: usize,
+    /// The `file:line` the argument was read from, if it came from a response file.
+    pub location: Option<String>
+}
+
+/// Expands every `@path` argument into the arguments contained in the file at `path`.
+/// Arguments following `--` are left untouched.
+pub fn expand(args: Vec<String>) -> Result<Vec<Expanded>, ArgsError> {
+    let mut expanded = Vec::new();
+    let mut stack = Vec::new();
+    let mut args = args.into_iter().enumerate();
+
+    while let Some((index, arg)) = args.next() {
+        if arg == END_OF_OPTIONS {
+            expanded.push(Expanded { arg, index, location: None });
+            expanded.extend(args.map(|(index, arg)| Expanded { arg, index, location: None }));
+            break;
+        }
+        expand_arg(SCOPE_PARSE, Path::new(""), arg, index, None, &mut stack, &mut expanded)?;
+    }
+    Ok(expanded)
+}
+
+/// Splits `contents` into arguments using shell-like rules: arguments are separated
+/// by whitespace, may be quoted with `'` or `"`, may escape characters with `\` and
+/// `#` begins a comment which runs to the end of the line. Each token is paired with
+/// the line it begins on.
+pub fn tokenize(name: &str, contents: &str) -> Result<Vec<(String, usize)>, ArgsError> {
+    let mut tokens = Vec::new();
+    let mut token: Option<String> = None;
+    let mut line = 1;
+    let mut token_line = line;
+    let mut chars = contents.chars();
+
+    while let Some(c) = chars.next() {
+        if token.is_none() { token_line = line; }
+        match c {
+            '\n' | ' ' | '\t' | '\r' => {
+                if let Some(token) = token.take() { tokens.push((token, token_line)); }
+            },
+            '#' if token.is_none() => {
+                // Skip the rest of the line
+                if chars.by_ref().any(|c| c == '\n') { line += 1; }
+                continue;
+            },
+            '\'' | '"' => {
+                let start = line;
+                let value = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => { break; },
+                        Some('\\') if c == '"' => match chars.next() {
+                            Some(escaped @ '"') | Some(escaped @ '\\') => value.push(escaped),
+                            Some(other) => {
+                                if other == '\n' { line += 1; }
+                                value.push('\\');
+                                value.push(other);
+                            },
+                            None => {}
+                        },
+                        Some(other) => {
+                            if other == '\n' { line += 1; }
+                            value.push(other);
+                        },
+                        None => {
+                            return Err(ArgsError::new(&format!("{}:{}", name, start), "unterminated quote")
+                                .with_kind(ErrorKind::ResponseFile));
+                        }
+                    }
+                }
+            },
+            '\\' => match chars.next() {
+                // An escaped newline continues the line
+                Some('\n') => { line += 1; },
+                Some(escaped) => token.get_or_insert_with(String::new).push(escaped),
+                None => {}
+            },
+            _ => token.get_or_insert_with(String::new).push(c)
+        }
+        if c == '\n' { line += 1; }
+    }
+    if let Some(token) = token { tokens.push((token, token_line)); }
+
+    Ok(tokens)
+}
+
+// Private associated methods
+fn expand_arg(origin: &str,
+        dir: &Path,
+        arg: String,
+        index: usize,
+        location: Option<String>,
+        stack: &mut Vec<PathBuf>,
+        expanded: &mut Vec<Expanded>) -> Result<(), ArgsError> {
+    if !arg.starts_with(PREFIX) || arg.len() == 1 {
+        expanded.push(Expanded { arg, index, location });
+        return Ok(());
+    }
+
+    // Nested response files are relative to the file which includes them
+    let path = dir.join(&arg[1..]);
+    let name = path.display().to_string();
+    let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    if stack.contains(&canonical) {
+        return Err(ArgsError::new(origin, &format!("response file '{}' includes itself", name))
+            .with_kind(ErrorKind::ResponseFile));
+    }
+
+    let contents = fs::read_to_string(&path).map_err(|error| {
+        ArgsError::new(origin, &format!("unable to read response file '{}': {}", name, error))
+            .with_kind(ErrorKind::ResponseFile)
+    })?;
+
+    stack.push(canonical);
+    let parent = path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+    for (token, line) in tokenize(&name, &contents)? {
+        let location = format!("{}:{}", name, line);
+        expand_arg(&name, &parent, token, index, Some(location), stack, expanded)?;
+    }
+    stack.pop();
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use response::Expanded;
+
+fn at(path: &Path) -> String {
+    format!("@{}", path.display())
+}
+
+fn args(expanded: Vec<Expanded>) -> Vec<String> {
+    expanded.into_iter().map(|expanded| expanded.arg).collect()
+}
+
+fn values(tokens: Vec<(String, usize)>) -> Vec<String> {
+    tokens.into_iter().map(|(token, _)| token).collect()
+}
+
+mod tokenize {
+    mod whitespace {
+        use response;
+        use super::super::values;
+
+        #[test]
+        fn separates_tokens() {
+            let tokens = response::tokenize("test.rsp", "-a  b\n\t--c=d\n").unwrap();
+
+            assert_eq!(vec!("-a", "b", "--c=d"), values(tokens));
+        }
+    }
+
+    mod quotes {
+        use response;
+        use super::super::values;
+
+        #[test]
+        fn preserve_whitespace() {
+            let tokens = response::tokenize("test.rsp", "'a b' \"c \\\"d\\\"\" e'f g'").unwrap();
+
+            assert_eq!(vec!("a b", "c \"d\"", "ef g"), values(tokens));
+        }
+    }
+
+    mod escapes {
+        use response;
+        use super::super::values;
+
+        #[test]
+        fn escape_next_character() {
+            let tokens = response::tokenize("test.rsp", "a\\ b c\\\nd").unwrap();
+
+            assert_eq!(vec!("a b", "cd"), values(tokens));
+        }
+    }
+
+    mod comments {
+        use response;
+        use super::super::values;
+
+        #[test]
+        fn are_skipped() {
+            let tokens = response::tokenize("test.rsp", "# comment\n-a # trailing\nb#c").unwrap();
+
+            assert_eq!(vec!("-a", "b#c"), values(tokens));
+        }
+    }
+
+    mod lines {
+        use response;
+
+        #[test]
+        fn are_where_tokens_begin() {
+            let tokens = response::tokenize("test.rsp", "-a # comment\n\n'b\nc' d\\\ne").unwrap();
+            let lines = tokens.into_iter().map(|(_, line)| line).collect::<Vec<usize>>();
+
+            assert_eq!(vec!(1, 3, 4), lines);
+        }
+    }
+
+    mod unterminated_quote {
+        use response;
+
+        #[test]
+        fn returns_err_with_location() {
+            let error = response::tokenize("test.rsp", "a\n'b\nc").unwrap_err();
+
+            assert_eq!("test.rsp:2: unterminated quote", error.to_string());
+        }
+    }
+}
+
+mod expand {
+    mod without_response_files {
+        use response;
+        use super::super::args;
+
+        #[test]
+        fn returns_args() {
+            let given = vec!("-a".to_string(), "@".to_string());
+
+            assert_eq!(given.clone(), args(response::expand(given).unwrap()));
+        }
+    }
+
+    mod nested {
+        use response;
+        use tst::TempDir;
+        use super::super::{args,at};
+
+        #[test]
+        fn expands_relative_to_including_file() {
+            let dir = TempDir::new();
+            dir.write("inner.rsp", "-b 'c d'");
+            let outer = dir.write("outer.rsp", "-a @inner.rsp");
+
+            let expanded = response::expand(vec!(at(&outer), "e".to_string())).unwrap();
+            assert_eq!(vec!("-a", "-b", "c d", "e"), args(expanded));
+        }
+
+        #[test]
+        fn keeps_index_of_given_argument() {
+            let dir = TempDir::new();
+            dir.write("indexed-inner.rsp", "-b");
+            let outer = dir.write("indexed-outer.rsp", "-a @indexed-inner.rsp");
+
+            let expanded = response::expand(vec!("-x".to_string(), at(&outer), "e".to_string())).unwrap();
+            let indices = expanded.into_iter().map(|expanded| expanded.index).collect::<Vec<usize>>();
+            assert_eq!(vec!(0, 1, 1, 2), indices);
+        }
+
+        #[test]
+        fn records_location_in_file() {
+            let dir = TempDir::new();
+            dir.write("located-inner.rsp", "-b\n\n-c");
+            let outer = dir.write("located-outer.rsp", "-a\n@located-inner.rsp");
+
+            let expanded = response::expand(vec!(at(&outer), "e".to_string())).unwrap();
+            let locations = expanded.into_iter().map(|expanded| expanded.location).collect::<Vec<Option<String>>>();
+            let inner = outer.with_file_name("located-inner.rsp").display().to_string();
+            assert_eq!(vec!(Some(format!("{}:1", outer.display())), Some(format!("{}:1", inner)),
+                Some(format!("{}:3", inner)), None), locations);
+        }
+    }
+
+    mod after_end_of_options {
+        use response;
+        use tst::TempDir;
+        use super::super::{args,at};
+
+        #[test]
+        fn leaves_args_untouched() {
+            let dir = TempDir::new();
+            let path = dir.write("untouched.rsp", "-a");
+            let given = vec!("--".to_string(), at(&path));
+
+            assert_eq!(given.clone(), args(response::expand(given).unwrap()));
+        }
+    }
+
+    mod cycle {
+        use response;
+        use tst::TempDir;
+        use super::super::at;
+
+        #[test]
+        fn returns_err_naming_file() {
+            let dir = TempDir::new();
+            dir.write("cycle-b.rsp", "@cycle-a.rsp");
+            let path = dir.write("cycle-a.rsp", "@cycle-b.rsp");
+
+            let error = response::expand(vec!(at(&path))).unwrap_err();
+            assert!(error.to_string().contains("includes itself"));
+            assert!(error.to_string().starts_with(&path.with_file_name("cycle-b.rsp").display().to_string()));
+        }
+    }
+
+    mod missing {
+        use response;
+        use tst::TempDir;
+        use super::super::at;
+
+        #[test]
+        fn returns_err_naming_origin() {
+            let dir = TempDir::new();
+            let path = dir.write("missing.rsp", "@nonexistent.rsp");
+
+            let error = response::expand(vec!(at(&path))).unwrap_err();
+            assert!(error.to_string().starts_with(&format!("{}: unable to read response file", path.display())));
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use super::{ArgsError,ErrorKind,SCOPE_PARSE};
+
+#[cfg(test)] mod tst;
+
+/// A relationship between two options which is enforced after parsing.
+pub enum Rule {
+    /// The first option may not be given along with the second.
+    ConflictsWith(String, String),
+    /// The first option may only be given along with the second.
+    Requires(String, String),
+    /// The first option must be given if the second has the provided value.
+    RequiredIfEq(String, String, String),
+    /// The first option must be given unless the second is.
+    RequiredUnlessPresent(String, String)
+}
+
+impl Rule {
+    /// Checks the rule against the options which were `given` and their `value`s.
+    pub fn check<G, V>(&self, given: G, value: V) -> Result<(), ArgsError>
+            where G: Fn(&str) -> bool, V: Fn(&str) -> Option<String> {
+        let (kind, msg) = match *self {
+            Rule::ConflictsWith(ref opt_name, ref other) if given(opt_name) && given(other) => {
+                (ErrorKind::Conflict, format!("Option '{}' cannot be used with '{}'", opt_name, other))
+            },
+            Rule::Requires(ref opt_name, ref other) if given(opt_name) && !given(other) => {
+                (ErrorKind::MissingRequired, format!("Option '{}' requires '{}'", opt_name, other))
+            },
+            Rule::RequiredIfEq(ref opt_name, ref other, ref expected)
+                    if !given(opt_name) && value(other).as_ref() == Some(expected) => {
+                (ErrorKind::MissingRequired,
+                    format!("Option '{}' is required when '{}' is '{}'", opt_name, other, expected))
+            },
+            Rule::RequiredUnlessPresent(ref opt_name, ref other) if !given(opt_name) && !given(other) => {
+                (ErrorKind::MissingRequired,
+                    format!("Option '{}' is required unless '{}' is present", opt_name, other))
+            },
+            _ => { return Ok(()); }
+        };
+
+        Err(ArgsError::new(SCOPE_PARSE, &msg).with_kind(kind).with_opt_name(self.names().0))
+    }
+
+    /// Returns the names of the two options related by this rule.
+    pub fn names(&self) -> (&str, &str) {
+        match *self {
+            Rule::ConflictsWith(ref opt_name, ref other) |
+            Rule::Requires(ref opt_name, ref other) |
+            Rule::RequiredIfEq(ref opt_name, ref other, _) |
+            Rule::RequiredUnlessPresent(ref opt_name, ref other) => (opt_name, other)
+        }
+    }
+
+    /// Returns the annotations describing this rule in the usage message, keyed by option.
+    pub fn notes(&self) -> Vec<(String, String)> {
+        match *self {
+            Rule::ConflictsWith(ref opt_name, ref other) => vec!(
+                (opt_name.to_string(), format!("[conflicts with: --{}]", other)),
+                (other.to_string(), format!("[conflicts with: --{}]", opt_name))
+            ),
+            Rule::Requires(ref opt_name, ref other) => vec!(
+                (opt_name.to_string(), format!("[requires: --{}]", other))
+            ),
+            Rule::RequiredIfEq(ref opt_name, ref other, ref expected) => vec!(
+                (opt_name.to_string(), format!("[required if: --{}={}]", other, expected))
+            ),
+            Rule::RequiredUnlessPresent(ref opt_name, ref other) => vec!(
+                (opt_name.to_string(), format!("[required unless: --{}]", other))
+            )
+        }
+    }
+}
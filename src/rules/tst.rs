@@ -0,0 +1,117 @@
+fn given(opt_name: &str) -> bool {
+    opt_name == "given"
+}
+
+fn value(opt_name: &str) -> Option<String> {
+    if opt_name == "given" { Some("value".to_string()) } else { None }
+}
+
+mod check {
+    mod conflicts_with {
+        mod both_given {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_err() {
+                let rule = Rule::ConflictsWith("given".to_string(), "given".to_string());
+                let error = rule.check(given, value).unwrap_err();
+
+                assert_eq!("parse: Option 'given' cannot be used with 'given'", error.to_string());
+            }
+        }
+
+        mod one_given {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_ok() {
+                let rule = Rule::ConflictsWith("given".to_string(), "absent".to_string());
+
+                assert!(rule.check(given, value).is_ok());
+            }
+        }
+    }
+
+    mod requires {
+        mod other_absent {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_err() {
+                let rule = Rule::Requires("given".to_string(), "absent".to_string());
+                let error = rule.check(given, value).unwrap_err();
+
+                assert_eq!("parse: Option 'given' requires 'absent'", error.to_string());
+            }
+        }
+
+        mod option_absent {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_ok() {
+                let rule = Rule::Requires("absent".to_string(), "absent".to_string());
+
+                assert!(rule.check(given, value).is_ok());
+            }
+        }
+    }
+
+    mod required_if_eq {
+        mod value_matches {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_err() {
+                let rule = Rule::RequiredIfEq("absent".to_string(), "given".to_string(), "value".to_string());
+                let error = rule.check(given, value).unwrap_err();
+
+                assert_eq!("parse: Option 'absent' is required when 'given' is 'value'", error.to_string());
+            }
+        }
+
+        mod value_differs {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_ok() {
+                let rule = Rule::RequiredIfEq("absent".to_string(), "given".to_string(), "other".to_string());
+
+                assert!(rule.check(given, value).is_ok());
+            }
+        }
+    }
+
+    mod required_unless_present {
+        mod both_absent {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_err() {
+                let rule = Rule::RequiredUnlessPresent("absent".to_string(), "missing".to_string());
+                let error = rule.check(given, value).unwrap_err();
+
+                assert_eq!("parse: Option 'absent' is required unless 'missing' is present", error.to_string());
+            }
+        }
+
+        mod other_given {
+            use rules::Rule;
+            use super::super::super::{given,value};
+
+            #[test]
+            fn returns_ok() {
+                let rule = Rule::RequiredUnlessPresent("absent".to_string(), "given".to_string());
+
+                assert!(rule.check(given, value).is_ok());
+            }
+        }
+    }
+}
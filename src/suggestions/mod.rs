@@ -0,0 +1,56 @@
+use std::cmp;
+use std::mem;
+
+#[cfg(test)] mod tst;
+
+/// Returns the `candidates` closest to `name`, ignoring any which are too far off to
+/// plausibly be a typo of it.
+pub fn closest<I, S>(name: &str, candidates: I) -> Vec<String> where I: IntoIterator<Item=S>, S: AsRef<str> {
+    let threshold = (name.chars().count() + 1) / 3;
+    let mut closest = Vec::new();
+    let mut best = threshold;
+
+    for candidate in candidates {
+        let candidate = candidate.as_ref();
+        let distance = distance(name, candidate);
+        if distance > best || candidate.is_empty() { continue; }
+
+        if distance < best { closest.clear(); }
+        best = distance;
+        if !closest.iter().any(|existing| existing == candidate) { closest.push(candidate.to_string()); }
+    }
+    closest
+}
+
+/// Calculates the number of insertions, deletions, substitutions and transpositions
+/// of adjacent characters needed to turn `a` into `b`.
+pub fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+
+    // Each row holds the distances from a prefix of `a` to every prefix of `b`
+    let mut current: Vec<usize> = (0..=b.len()).collect();
+    let mut previous = current.clone();
+    for i in 1..=a.len() {
+        let before = mem::replace(&mut previous, current.clone());
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = cmp::min(cmp::min(previous[j] + 1, current[j - 1] + 1), previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = cmp::min(current[j], before[j - 2] + 1);
+            }
+        }
+    }
+    current[b.len()]
+}
+
+/// Formats `suggestions` as a hint to append to an error message, if there are any.
+pub fn hint(suggestions: &[String]) -> String {
+    if suggestions.is_empty() { return String::new(); }
+
+    let quoted = suggestions.iter()
+        .map(|suggestion| format!("'{}'", suggestion))
+        .collect::<Vec<String>>();
+    format!(". Did you mean {}?", quoted.join(" or "))
+}
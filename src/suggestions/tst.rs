@@ -0,0 +1,64 @@
+mod closest {
+    mod typo {
+        use suggestions;
+
+        #[test]
+        fn returns_closest() {
+            assert_eq!(vec!("verbose".to_string()),
+                suggestions::closest("verbsoe", ["version", "verbose", "v"]));
+        }
+    }
+
+    mod tie {
+        use suggestions;
+
+        #[test]
+        fn returns_all() {
+            assert_eq!(vec!("build".to_string(), "guild".to_string()),
+                suggestions::closest("uild", ["build", "guild", "test"]));
+        }
+    }
+
+    mod unrelated {
+        use suggestions;
+
+        #[test]
+        fn returns_empty() {
+            assert!(suggestions::closest("output", ["verbose", "o"]).is_empty());
+        }
+    }
+}
+
+mod distance {
+    use suggestions;
+
+    #[test]
+    fn counts_edits() {
+        assert_eq!(3, suggestions::distance("kitten", "sitting"));
+    }
+
+    #[test]
+    fn counts_transposition_once() {
+        assert_eq!(1, suggestions::distance("verbsoe", "verbose"));
+    }
+
+    #[test]
+    fn counts_empty() {
+        assert_eq!(4, suggestions::distance("", "test"));
+    }
+}
+
+mod hint {
+    use suggestions;
+
+    #[test]
+    fn joins_suggestions() {
+        assert_eq!(". Did you mean '--build' or '--guild'?",
+            suggestions::hint(&["--build".to_string(), "--guild".to_string()]));
+    }
+
+    #[test]
+    fn returns_empty() {
+        assert_eq!("", suggestions::hint(&[]));
+    }
+}
@@ -1,6 +1,6 @@
 use std::ffi::OsStr;
 
-use super::super::{Args,ArgsError};
+use super::super::{Args,ArgsError,ParseOutcome};
 
 /// A trait designed to bind associated convenience methods to a struct.
 pub trait HasArgs: Send {
@@ -15,12 +15,12 @@ pub trait HasArgs: Send {
     }
 
     /// Acts as a convenience method for calling the associated `Args` implementation.
-    fn parse<C: IntoIterator>(raw_args: C) -> Result<(), ArgsError> where C::Item: AsRef<OsStr>, Self: Sized {
+    fn parse<C: IntoIterator>(raw_args: C) -> Result<ParseOutcome, ArgsError> where C::Item: AsRef<OsStr>, Self: Sized {
         Self::args().parse(raw_args)
     }
 
     /// Acts as a convenience method for calling the associated `Args` implementation.
-    fn parse_from_cli() -> Result<(), ArgsError> where Self: Sized {
+    fn parse_from_cli() -> Result<ParseOutcome, ArgsError> where Self: Sized {
         Self::args().parse_from_cli()
     }
 
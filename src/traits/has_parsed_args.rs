@@ -1,6 +1,7 @@
+use std::error::Error;
 use std::str::FromStr;
 
-use super::super::{Args,ArgsError};
+use super::super::{Args,ArgsError,MaybeValue};
 use super::super::validations::Validation;
 
 /// A trait designed to bind parsed arguments and instance methods to a struct.
@@ -16,32 +17,54 @@ pub trait HasParsedArgs: Send {
     }
 
     /// Acts as a convenience method for calling the `Args` implementation.
-    fn optional_validated_value_of<T>(&self, opt_name: &str, validations: &[Box<Validation<T=T>>])
-                                      -> Result<Option<T>, ArgsError> where T: FromStr {
+    fn maybe_value_of<T: FromStr>(&self, opt_name: &str) -> Result<MaybeValue<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.parsed_args().maybe_value_of::<T>(opt_name)
+    }
+
+    /// Acts as a convenience method for calling the `Args` implementation.
+    fn optional_validated_value_of<T>(&self, opt_name: &str, validations: &[Box<dyn Validation<T=T>>])
+                                      -> Result<Option<T>, ArgsError>
+                                      where T: FromStr, T::Err: Into<Box<dyn Error + Send + Sync>> {
         if self.has_value(opt_name) {
-            Ok(Some(try!(self.validated_value_of::<T>(opt_name, validations))))
+            Ok(Some(self.validated_value_of::<T>(opt_name, validations)?))
         } else {
             Ok(None)
         }
     }
 
     /// Acts as a convenience method for calling the `Args` implementation.
-    fn optional_value_of<T: FromStr>(&self, opt_name: &str) -> Result<Option<T>, ArgsError> {
+    fn optional_value_of<T: FromStr>(&self, opt_name: &str) -> Result<Option<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
         if self.has_value(opt_name) {
-            Ok(Some(try!(self.value_of::<T>(opt_name))))
+            Ok(Some(self.value_of::<T>(opt_name)?))
         } else {
             Ok(None)
         }
     }
 
     /// Acts as a convenience method for calling the `Args` implementation.
-    fn validated_value_of<T>(&self, opt_name: &str, validations: &[Box<Validation<T=T>>])
-        -> Result<T, ArgsError> where T: FromStr {
+    fn positional_of<T: FromStr>(&self, name: &str) -> Result<T, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.parsed_args().positional_of::<T>(name)
+    }
+
+    /// Acts as a convenience method for calling the `Args` implementation.
+    fn positionals_of<T: FromStr>(&self, name: &str) -> Result<Vec<T>, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
+        self.parsed_args().positionals_of::<T>(name)
+    }
+
+    /// Acts as a convenience method for calling the `Args` implementation.
+    fn validated_value_of<T>(&self, opt_name: &str, validations: &[Box<dyn Validation<T=T>>])
+        -> Result<T, ArgsError>
+        where T: FromStr, T::Err: Into<Box<dyn Error + Send + Sync>> {
         self.parsed_args().validated_value_of::<T>(opt_name, validations)
     }
 
     /// Acts as a convenience method for calling the `Args` implementation.
-    fn value_of<T: FromStr>(&self, opt_name: &str) -> Result<T, ArgsError> {
+    fn value_of<T: FromStr>(&self, opt_name: &str) -> Result<T, ArgsError>
+        where T::Err: Into<Box<dyn Error + Send + Sync>> {
         self.parsed_args().value_of::<T>(opt_name)
     }
 }
@@ -11,281 +11,2513 @@ macro_rules! args {
     }};
 }
 
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize,Ordering};
+
+/// A scratch directory unique to one test, removed when dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> TempDir {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!("args-{}-{}", process::id(), COUNT.fetch_add(1, Ordering::SeqCst));
+        let path = std::env::temp_dir().join(name);
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+
+    pub fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.path.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+mod alias {
+    use Args;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.option("o", "output", "Output file", "FILE", Occur::Optional, None);
+        args.flag("v", "verbose", "Verbose");
+        args
+    }
+
+    mod hidden {
+        use MaybeValue;
+        use super::args;
+
+        #[test]
+        fn maps_long_alias_to_option() {
+            let mut args = args();
+            args.alias("output", "out-file");
+            args.parse(&vec!("--out-file", "out.txt")).unwrap();
+
+            assert_eq!("out.txt".to_string(), args.value_of::<String>("output").unwrap());
+        }
+
+        #[test]
+        fn maps_short_alias_to_option() {
+            let mut args = args();
+            args.alias("output", "O");
+            args.parse(&vec!("-vO", "out.txt")).unwrap();
+
+            assert_eq!("out.txt".to_string(), args.value_of::<String>("output").unwrap());
+            assert!(args.value_of::<bool>("verbose").unwrap());
+        }
+
+        #[test]
+        fn maps_short_alias_to_maybe_option_value() {
+            let mut args = args();
+            args.maybe_option("", "color", "Colorize output", "WHEN", None);
+            args.alias("color", "k");
+            args.parse(&vec!("-k", "never")).unwrap();
+
+            assert_eq!(MaybeValue::Value("never".to_string()), args.maybe_value_of::<String>("color").unwrap());
+        }
+
+        #[test]
+        fn maps_negated_alias_to_negatable_flag() {
+            let mut args = args();
+            args.negatable_flag("", "color", "Colorize output", true);
+            args.alias("color", "colour");
+            args.parse(&vec!("--no-colour")).unwrap();
+
+            assert!(!args.value_of::<bool>("color").unwrap());
+        }
+
+        #[test]
+        fn is_not_in_usage() {
+            let mut args = args();
+            args.alias("output", "out-file");
+
+            assert!(!args.usage().contains("out-file"));
+        }
+    }
+
+    mod visible {
+        use Shell;
+        use super::args;
+
+        #[test]
+        fn is_in_usage() {
+            let mut args = args();
+            args.visible_alias("output", "out-file");
+
+            assert!(args.usage().contains("Output file [alias: --out-file]"));
+        }
+
+        #[test]
+        fn is_in_completions() {
+            let mut args = args();
+            args.visible_alias("output", "out-file");
+            args.visible_alias("output", "O");
+
+            let completions = args.completions(Shell::Fish);
+            assert!(completions.contains("-s o -s O -l output -l out-file"));
+        }
+
+        #[test]
+        fn is_in_references() {
+            let mut args = args();
+            args.visible_alias("output", "out-file");
+
+            assert!(args.man_page().contains("out\\-file"));
+            assert!(args.markdown_reference().contains("--out-file"));
+            assert!(args.html_reference().contains("--out-file"));
+        }
+    }
+
+    mod conflicting {
+        use super::args;
+
+        #[test]
+        fn is_ignored() {
+            let mut args = args();
+            args.alias("output", "verbose");
+            args.parse(&vec!("--verbose")).unwrap();
+
+            assert!(args.value_of::<bool>("verbose").unwrap());
+        }
+    }
+}
+
+mod collect_errors {
+    mod disabled {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn returns_first_err() {
+            let mut args = args!(Occur::Req, None);
+            let err = args.parse(&vec!("--bad")).unwrap_err();
+            assert_eq!("parse: Unrecognized option: 'bad'", err.to_string());
+            assert_eq!(1, err.errors().count());
+        }
+    }
+
+    mod enabled {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn returns_all_errs() {
+            let mut args = args!(Occur::Req, None);
+            args.option("m", "mode", "Mode", "MODE", Occur::Optional, None);
+            args.possible_values("mode", &["fast", "slow"]);
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("--bad", "-x", "-m", "medium")).unwrap_err();
+            assert_eq!(ErrorKind::Multiple, err.kind());
+            let kinds = err.errors().map(|error| error.kind()).collect::<Vec<ErrorKind>>();
+            assert_eq!(vec!(ErrorKind::UnrecognizedOption,
+                ErrorKind::UnrecognizedOption,
+                ErrorKind::MissingRequired,
+                ErrorKind::Validation), kinds);
+            assert_eq!(r#"parse: 4 errors occurred:
+  parse: Unrecognized option: 'bad'
+  parse: Unrecognized option: 'x'
+  parse: Required option 'option' missing
+  parse: Invalid value 'medium' for option 'mode' (possible values: fast, slow)"#, err.to_string());
+        }
+
+        #[test]
+        fn keeps_known_options() {
+            let mut args = args!();
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("-xf")).unwrap_err();
+            assert_eq!(ErrorKind::UnrecognizedOption, err.kind());
+            assert_eq!("true", args.value_of::<String>("flag").unwrap());
+        }
+
+        #[test]
+        fn keeps_attached_values() {
+            let mut args = args!(Occur::Optional, None);
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("-ofoo", "-f")).unwrap_err();
+            assert_eq!(Some("f"), err.opt_name());
+            assert_eq!(Some(2), err.position());
+            assert_eq!("foo", args.value_of::<String>("option").unwrap());
+        }
+
+        #[test]
+        fn keeps_separate_values() {
+            let mut args = args!(Occur::Optional, None);
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("-o", "-f", "-f")).unwrap_err();
+            assert_eq!(Some("f"), err.opt_name());
+            assert_eq!(Some(3), err.position());
+            assert_eq!("-f", args.value_of::<String>("option").unwrap());
+        }
+
+        #[test]
+        fn finds_short_duplicate_flags() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("i", "iter", "Iterations", "ITER", Occur::Req, None);
+            args.flag("v", "verbose", "Verbose");
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("-v", "-v", "--bogus")).unwrap_err();
+            let kinds = err.errors().map(|error| error.kind()).collect::<Vec<ErrorKind>>();
+            assert_eq!(vec!(ErrorKind::UnrecognizedOption,
+                ErrorKind::DuplicateOption,
+                ErrorKind::MissingRequired), kinds);
+            assert_eq!(Some(2), err.errors().nth(1).unwrap().position());
+        }
+
+        #[test]
+        fn finds_short_duplicate_options() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("i", "iter", "Iterations", "ITER", Occur::Optional, None);
+            args.flag("v", "verbose", "Verbose");
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("-i", "1", "-i", "2", "--bogus")).unwrap_err();
+            let kinds = err.errors().map(|error| error.kind()).collect::<Vec<ErrorKind>>();
+            assert_eq!(vec!(ErrorKind::UnrecognizedOption, ErrorKind::DuplicateOption), kinds);
+            assert_eq!("1", args.value_of::<String>("iter").unwrap());
+        }
+
+        #[test]
+        fn returns_ok_without_errs() {
+            let mut args = args!();
+            args.collect_errors(true);
+            assert!(args.parse(&vec!("-f")).is_ok());
+        }
+    }
+
+    mod rules {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn returns_all_errs() {
+            let mut args = args!(Occur::Optional, None);
+            args.flag("f", "flag", "Flag");
+            args.flag("q", "quiet", "Quiet");
+            args.flag("v", "verbose", "Verbose");
+            args.conflicts_with("quiet", "verbose");
+            args.requires("flag", "option");
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("-f", "-q", "-v")).unwrap_err();
+            let kinds = err.into_iter().map(|error| error.kind()).collect::<Vec<ErrorKind>>();
+            assert_eq!(vec!(ErrorKind::Conflict, ErrorKind::MissingRequired), kinds);
+        }
+    }
+
+    mod subcommand {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn returns_errs_of_both() {
+            let mut args = args!(Occur::Req, None);
+            args.subcommand("build", "Build the project")
+                .option("t", "target", "Target", "TARGET", Occur::Req, None);
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("build")).unwrap_err();
+            let opt_names = err.errors().map(|error| error.opt_name().unwrap()).collect::<Vec<&str>>();
+            assert_eq!(vec!("option", "target"), opt_names);
+            assert!(err.errors().all(|error| error.kind() == ErrorKind::MissingRequired));
+        }
+    }
+
+    mod validated_value_of {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+        use validations::{Order,OrderValidation,Validation};
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_all_errs() {
+            let mut args = args!(Occur::Req, None);
+            args.collect_errors(true);
+            args.parse(&vec!("-o", "5"));
+
+            let lt_1: Box<dyn Validation<T=i32>> = Box::new(OrderValidation::new(Order::LessThan, 1));
+            let lt_2: Box<dyn Validation<T=i32>> = Box::new(OrderValidation::new(Order::LessThan, 2));
+            let gt_0: Box<dyn Validation<T=i32>> = Box::new(OrderValidation::new(Order::GreaterThan, 0));
+            let err = args.validated_value_of::<i32>("option", &[lt_1, gt_0, lt_2]).unwrap_err();
+            assert_eq!(ErrorKind::Multiple, err.kind());
+            assert_eq!(r#"option: 2 errors occurred:
+  option: invalid value '5': order invalid: 5 is not less than 1
+  option: invalid value '5': order invalid: 5 is not less than 2"#, err.to_string());
+        }
+    }
+}
+
+mod completions {
+    use Args;
+    use Shell;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.option("m", "mode", "Mode", "MODE", Occur::Optional, None);
+        args.possible_values("mode", &["fast", "slow"]);
+        args.subcommand("build", "Build the project");
+        args
+    }
+
+    #[test]
+    fn includes_possible_values() {
+        assert!(args().completions(Shell::Bash)
+            .contains("-m|--mode) COMPREPLY=($(compgen -W \"fast slow\" -- \"${cur}\")); return 0 ;;"));
+    }
+
+    #[test]
+    fn includes_subcommands() {
+        assert!(args().completions(Shell::Fish)
+            .contains("complete -c program -n 'not __fish_seen_subcommand_from build' -f -a build -d 'Build the project'"));
+    }
+
+    #[test]
+    fn includes_descriptions() {
+        assert!(args().completions(Shell::Zsh)
+            .contains("'(-m --mode)'{-m,--mode}'[Mode \\[possible values: fast, slow\\]]:MODE:(fast slow)'"));
+    }
+}
+
+mod config {
+    mod absent {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_default() {
+            let mut args = args!(Occur::Optional, Some("default".to_string()));
+            args.config_file("/nonexistent/args.toml");
+            args.parse(&vec!(""));
+
+            assert_eq!("default".to_string(), args.value_of::<String>("option").unwrap());
+        }
+    }
+
+    mod present {
+        mod cli_absent {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_config_value() {
+                let dir = TempDir::new();
+                let path = dir.write("cli-absent.toml", "option = \"config\"\nflag = true\n");
+                let mut args = args!(Occur::Req, None);
+                args.flag("f", "flag", "Flag").config_file(&path);
+
+                assert!(args.parse(&vec!("")).is_ok());
+                assert_eq!("config".to_string(), args.value_of::<String>("option").unwrap());
+                assert!(args.value_of::<bool>("flag").unwrap());
+            }
+        }
+
+        mod cli_present {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_cli_value() {
+                let dir = TempDir::new();
+                let path = dir.write("cli-present.toml", "option = config");
+                let mut args = args!(Occur::Optional, None);
+                args.config_file(&path);
+                args.parse(&vec!("-o", "cli"));
+
+                assert_eq!("cli".to_string(), args.value_of::<String>("option").unwrap());
+            }
+        }
+
+        mod env_present {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_env_value() {
+                let dir = TempDir::new();
+                let path = dir.write("env-present.toml", "option = config");
+                let mut args = args!(Occur::Optional, None);
+                args.config_file(&path).env("option", "OPTION").env_lookup(|_| Some("env".to_string()));
+                args.parse(&vec!(""));
+
+                assert_eq!("env".to_string(), args.value_of::<String>("option").unwrap());
+            }
+        }
+
+        mod multi {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_array_values() {
+                let dir = TempDir::new();
+                let path = dir.write("multi.toml", "option = [\"a,b\", \"c\"]");
+                let mut args = args!(Occur::Multi, None);
+                args.config_file(&path);
+                args.parse(&vec!(""));
+
+                assert_eq!(vec!("a,b".to_string(), "c".to_string()), args.values_of::<String>("option").unwrap());
+            }
+        }
+
+        mod section {
+            use Args;
+            use tst::TempDir;
+
+            #[test]
+            fn applies_to_subcommand() {
+                let dir = TempDir::new();
+                let path = dir.write("section.ini", "[build]\nrelease = yes\n");
+                let mut args = args!();
+                args.subcommand("build", "Build the project").flag("r", "release", "Release");
+                args.config_file(&path);
+
+                assert!(args.parse(&vec!("build")).is_ok());
+
+                assert!(args.subcommand_args().unwrap().value_of::<bool>("release").unwrap());
+            }
+        }
+
+        mod unknown_key {
+            use Args;
+            use tst::TempDir;
+
+            #[test]
+            fn returns_err_naming_location() {
+                let dir = TempDir::new();
+                let path = dir.write("unknown-key.ini", "\nflga = true\n");
+                let mut args = args!();
+                args.config_file(&path);
+
+                let error = args.parse(&vec!("")).unwrap_err();
+                assert_eq!(format!("{}:2: unknown key 'flga'", path.display()), error.to_string());
+            }
+        }
+
+        mod invalid_value {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            fn returns_err_naming_location() {
+                let dir = TempDir::new();
+                let path = dir.write("invalid-value.ini", "flag = sometimes");
+                let mut args = args!();
+                args.config_file(&path);
+
+                let error = args.parse(&vec!("")).unwrap_err();
+                assert_eq!(format!("{}:1: invalid value for key 'flag'", path.display()), error.to_string());
+            }
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn names_entry_on_parse_err() {
+                let dir = TempDir::new();
+                let path = dir.write("invalid-value.toml", "# server\noption = abc\n");
+                let mut args = args!(Occur::Optional, None);
+                args.config_file(&path);
+                args.parse(&vec!(""));
+
+                let err = args.value_of::<u16>("option").unwrap_err();
+                assert_eq!(Some(format!("{}:2", path.display())), err.location().map(|location| location.to_string()));
+                assert_eq!(format!("option: unable to parse 'abc': invalid digit found in string (from {}:2)", path.display()),
+                    err.to_string());
+            }
+
+            #[test]
+            fn names_entry_on_impossible_value() {
+                let dir = TempDir::new();
+                let path = dir.write("impossible-value.toml", "option = medium\n");
+                let mut args = args!(Occur::Optional, None);
+                args.possible_values("option", &["fast", "slow"]);
+                args.config_file(&path);
+
+                let err = args.parse(&vec!("")).unwrap_err();
+                assert_eq!(Some(format!("{}:1", path.display())), err.location().map(|location| location.to_string()));
+            }
+        }
+    }
+
+    mod config_option {
+        mod given {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn reads_given_path() {
+                let dir = TempDir::new();
+                let path = dir.write("config-option.toml", "option = config");
+                let mut args = args!(Occur::Optional, None);
+                args.option("c", "config", "Config", "FILE", Occur::Optional, None).config_option("config");
+                args.parse(&vec!("-c", path.to_str().unwrap()));
+
+                assert_eq!("config".to_string(), args.value_of::<String>("option").unwrap());
+            }
+        }
+
+        mod given_missing {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            fn returns_err() {
+                let mut args = args!(Occur::Optional, None);
+                args.option("c", "config", "Config", "FILE", Occur::Optional, None).config_option("config");
+
+                assert!(args.parse(&vec!("-c", "/nonexistent/args.toml")).is_err());
+            }
+        }
+    }
+}
+
+mod deprecated {
+    use Args;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.option("o", "out", "Output file", "FILE", Occur::Optional, None);
+        args.option("O", "output", "Output file", "FILE", Occur::Optional, Some("-".to_string()));
+        args.deprecated("out", "renamed for clarity", Some("output"));
+        args
+    }
+
+    mod given {
+        use super::args;
+
+        #[test]
+        fn records_warning() {
+            let mut args = args();
+            args.parse(&vec!("--out", "out.txt")).unwrap();
+
+            assert_eq!(vec!("Option 'out' is deprecated: renamed for clarity (use 'output' instead)".to_string()),
+                args.warnings());
+        }
+
+        #[test]
+        fn forwards_value() {
+            let mut args = args();
+            args.parse(&vec!("--out", "out.txt")).unwrap();
+
+            assert_eq!("out.txt".to_string(), args.value_of::<String>("output").unwrap());
+        }
+
+        #[test]
+        fn does_not_override_replacement() {
+            let mut args = args();
+            args.parse(&vec!("--out", "out.txt", "--output", "output.txt")).unwrap();
+
+            assert_eq!("output.txt".to_string(), args.value_of::<String>("output").unwrap());
+        }
+    }
+
+    mod absent {
+        use super::args;
+
+        #[test]
+        fn records_no_warning() {
+            let mut args = args();
+            args.parse(&vec!("--output", "output.txt")).unwrap();
+
+            assert!(args.warnings().is_empty());
+        }
+    }
+
+    mod usage {
+        use super::args;
+
+        #[test]
+        fn annotates_option() {
+            assert!(args().usage().contains("Output file [deprecated: use --output]"));
+        }
+
+        #[test]
+        fn omits_from_short_usage() {
+            assert_eq!("Usage: program [-O FILE]", args().short_usage());
+        }
+    }
+}
+
+mod env {
+    mod cli_present {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_cli_value() {
+            let mut args = args!(Occur::Optional, Some("default".to_string()));
+            args.env("option", "OPTION").env_lookup(|_| Some("env".to_string()));
+            args.parse(&vec!("-o", "cli"));
+
+            assert_eq!("cli".to_string(), args.value_of::<String>("option").unwrap());
+        }
+    }
+
+    mod cli_absent {
+        mod env_present {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_env_value() {
+                let mut args = args!(Occur::Optional, Some("default".to_string()));
+                args.env("option", "OPTION").env_lookup(|name| {
+                    if name == "OPTION" { Some("env".to_string()) } else { None }
+                });
+                args.parse(&vec!(""));
+
+                assert_eq!("env".to_string(), args.value_of::<String>("option").unwrap());
+            }
+        }
+
+        mod env_absent {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_default() {
+                let mut args = args!(Occur::Optional, Some("default".to_string()));
+                args.env("option", "OPTION").env_lookup(|_| None);
+                args.parse(&vec!(""));
+
+                assert_eq!("default".to_string(), args.value_of::<String>("option").unwrap());
+            }
+        }
+
+        mod required {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            fn returns_ok() {
+                let mut args = args!(Occur::Req, None);
+                args.env("option", "OPTION").env_lookup(|_| Some("env".to_string()));
+
+                assert!(args.parse(&vec!("")).is_ok());
+            }
+        }
+    }
+
+    mod flag {
+        mod truthy {
+            use Args;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_true() {
+                let mut args = args!();
+                args.env("flag", "FLAG").env_lookup(|_| Some("on".to_string()));
+                args.parse(&vec!(""));
+
+                assert!(args.value_of::<bool>("flag").unwrap());
+            }
+        }
+
+        mod invalid {
+            use Args;
+
+            #[test]
+            fn returns_err() {
+                let mut args = args!();
+                args.env("flag", "FLAG").env_lookup(|_| Some("sometimes".to_string()));
+
+                assert!(args.parse(&vec!("")).is_err());
+            }
+        }
+    }
+
+    mod multi {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_delimited_values() {
+            let mut args = args!(Occur::Multi, None);
+            args.env("option", "OPTION").env_lookup(|_| Some("a,b".to_string()));
+            args.parse(&vec!(""));
+
+            assert_eq!(vec!("a".to_string(), "b".to_string()), args.values_of::<String>("option").unwrap());
+        }
+    }
+
+    mod usage {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn includes_variable() {
+            let mut args = args!(Occur::Optional, None);
+            args.env("option", "MYAPP_OPTION");
+
+            assert!(args.usage().contains("Option [env: MYAPP_OPTION]"));
+        }
+    }
+}
+
+mod diagnostic {
+    mod attached_value {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn underlines_arg() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("i", "iter", "Iterations", "ITER", Occur::Req, None);
+            args.flag("v", "verbose", "Verbose");
+            args.parse(&vec!("--iter=abc", "-v"));
+
+            let err = args.value_of::<i32>("iter").unwrap_err();
+            assert_eq!(Some(1), err.position());
+            assert_eq!(r#"iter: unable to parse 'abc': invalid digit found in string
+  program --iter=abc -v
+          ^^^^^^^^^^"#, err.diagnostic());
+        }
+    }
+
+    mod separate_value {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn underlines_opt_and_value() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("i", "iter", "Iterations", "ITER", Occur::Multi, None);
+            args.parse(&vec!("-i", "1", "-i", "two words"));
+
+            let err = args.values_of::<i32>("iter").unwrap_err();
+            assert_eq!(Some(3), err.position());
+            assert_eq!(r#"iter: unable to parse 'two words': invalid digit found in string
+  program -i 1 -i 'two words'
+               ^^^^^^^^^^^^^^"#, err.diagnostic());
+        }
+    }
+
+    mod alias {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn underlines_alias_as_given() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("i", "iter", "Iterations", "ITER", Occur::Req, None);
+            args.alias("iter", "I");
+            args.parse(&vec!("-I", "abc"));
+
+            let err = args.value_of::<i32>("iter").unwrap_err();
+            assert_eq!(Some(1), err.position());
+            assert_eq!(r#"iter: unable to parse 'abc': invalid digit found in string
+  program -I abc
+          ^^^^^^"#, err.diagnostic());
+        }
+    }
+
+    mod response_file {
+        use Args;
+        use getopts::Occur;
+        use tst::TempDir;
+
+        #[test]
+        fn underlines_file_argument() {
+            let dir = TempDir::new();
+            let path = dir.write("args.rsp", "# flags\n\n--bad\n");
+            let mut args = args!();
+            args.response_files(true);
+            args.alias("flag", "F");
+
+            let file_arg = format!("@{}", path.display());
+            let err = args.parse(vec!("-F".to_string(), file_arg.to_string())).unwrap_err();
+            assert_eq!(Some(2), err.position());
+            assert_eq!(Some(format!("{}:3", path.display())), err.location().map(|location| location.to_string()));
+            assert_eq!(format!(r#"parse: Unrecognized option: 'bad' (from {}:3)
+  program -F {}
+             {}"#, path.display(), file_arg, "^".repeat(file_arg.len())), err.diagnostic());
+        }
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn names_line_of_value() {
+            let dir = TempDir::new();
+            let path = dir.write("args.rsp", "-f\n--iter abc\n");
+            let mut args = args!();
+            args.option("i", "iter", "Iterations", "ITER", Occur::Optional, None);
+            args.response_files(true);
+            args.parse(vec!(format!("@{}", path.display())));
+
+            let err = args.value_of::<i32>("iter").unwrap_err();
+            assert_eq!(format!("iter: unable to parse 'abc': invalid digit found in string (from {}:2)", path.display()),
+                err.to_string());
+        }
+    }
+
+    mod duplicate_option {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn underlines_short_duplicate() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("i", "iter", "Iterations", "ITER", Occur::Optional, None);
+
+            let err = args.parse(&vec!("-i", "1", "-i", "2")).unwrap_err();
+            assert_eq!(Some(3), err.position());
+            assert_eq!(r#"parse: Option 'iter' given more than once
+  program -i 1 -i 2
+               ^^"#, err.diagnostic());
+        }
+    }
+
+    mod unrecognized_option {
+        use Args;
+
+        #[test]
+        fn underlines_cluster() {
+            let err = args!().parse(&vec!("a", "-fx")).unwrap_err();
+            assert_eq!(Some(2), err.position());
+            assert_eq!(r#"parse: Unrecognized option: 'x'
+  program a -fx
+            ^^^"#, err.diagnostic());
+        }
+    }
+
+    mod subcommand {
+        use Args;
+
+        #[test]
+        fn underlines_arg_after_command() {
+            let mut args = args!();
+            args.subcommand("build", "Build the project");
+
+            let err = args.parse(&vec!("-f", "build", "--bad")).unwrap_err();
+            assert_eq!(Some(3), err.position());
+            assert_eq!(r#"parse: Unrecognized option: 'bad'
+  program -f build --bad
+                   ^^^^^"#, err.diagnostic());
+        }
+
+        #[test]
+        fn underlines_unrecognized_command() {
+            let mut args = args!();
+            args.subcommand("build", "Build the project");
+
+            let err = args.parse(&vec!("-f", "biuld")).unwrap_err();
+            assert_eq!(Some(2), err.position());
+        }
+    }
+
+    mod multiple {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn underlines_each_err() {
+            let mut args = args!(Occur::Optional, None);
+            args.possible_values("option", &["a", "b"]);
+            args.collect_errors(true);
+
+            let err = args.parse(&vec!("--bad", "-o", "c")).unwrap_err();
+            assert_eq!(r#"parse: 2 errors occurred:
+  parse: Unrecognized option: 'bad'
+    program --bad -o c
+            ^^^^^
+  parse: Invalid value 'c' for option 'option' (possible values: a, b)
+    program --bad -o c
+                  ^^^^"#, err.diagnostic());
+        }
+    }
+
+    mod unknown_position {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn is_plain_text() {
+            let err = args!(Occur::Req, None).parse(&Vec::<&str>::new()).unwrap_err();
+            assert_eq!(None, err.position());
+            assert_eq!(err.to_string(), err.diagnostic());
+        }
+    }
+}
+
+mod errors {
+    mod unrecognized_option {
+        use {Args,ErrorKind};
+
+        #[test]
+        fn has_kind_and_opt_name() {
+            let err = args!().parse(&vec!("--flg")).unwrap_err();
+            assert_eq!(ErrorKind::UnrecognizedOption, err.kind());
+            assert_eq!(Some("flg"), err.opt_name());
+            assert_eq!("parse: Unrecognized option: 'flg'. Did you mean '--flag'?", err.to_string());
+        }
+    }
+
+    mod argument_missing {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn has_kind_and_opt_name() {
+            let err = args!(Occur::Optional, None).parse(&vec!("-o")).unwrap_err();
+            assert_eq!(ErrorKind::MissingArgument, err.kind());
+            assert_eq!(Some("o"), err.opt_name());
+            assert_eq!("parse", err.scope());
+            assert_eq!("Argument to option 'o' missing", err.message());
+        }
+    }
+
+    mod required_missing {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn has_kind_and_opt_name() {
+            let err = args!(Occur::Req, None).parse(&Vec::<&str>::new()).unwrap_err();
+            assert_eq!(ErrorKind::MissingRequired, err.kind());
+            assert_eq!(Some("option"), err.opt_name());
+            assert_eq!("parse: Required option 'option' missing", err.to_string());
+        }
+    }
+
+    mod unexpected_argument {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn has_kind_and_value() {
+            let mut args = args!();
+            args.positional("file", "File", "FILE", Occur::Optional);
+
+            let err = args.parse(&vec!("a.txt", "extra")).unwrap_err();
+            assert_eq!(ErrorKind::UnexpectedArgument, err.kind());
+            assert_eq!(Some("extra"), err.value());
+            assert_eq!(None, err.opt_name());
+        }
+    }
+
+    mod parse_value {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn has_kind_opt_name_and_value() {
+            let mut args = args!(Occur::Req, None);
+            args.parse(&vec!("-o", "value"));
+
+            let err = args.value_of::<i32>("option").unwrap_err();
+            assert_eq!(ErrorKind::ParseValue, err.kind());
+            assert_eq!(Some("option"), err.opt_name());
+            assert_eq!(Some("value"), err.value());
+            assert_eq!("option: unable to parse 'value': invalid digit found in string", err.to_string());
+        }
+    }
+
+    mod parse_value_source {
+        use std::error::Error;
+        use std::num::ParseIntError;
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn is_from_str_error() {
+            let mut args = args!(Occur::Req, None);
+            args.parse(&vec!("-o", "99999999999"));
+
+            let err = args.value_of::<i32>("option").unwrap_err();
+            let source = err.source().unwrap();
+            assert!(source.downcast_ref::<ParseIntError>().is_some());
+            assert_eq!("number too large to fit in target type", source.to_string());
+        }
+    }
+
+    mod parse_value_string_error {
+        use std::error::Error;
+        use std::str::FromStr;
+        use Args;
+        use getopts::Occur;
+
+        #[derive(Debug)]
+        enum Mode { Fast }
+
+        impl FromStr for Mode {
+            type Err = String;
+
+            fn from_str(value: &str) -> Result<Mode, String> {
+                match value {
+                    "fast" => Ok(Mode::Fast),
+                    _ => Err(format!("unknown mode '{}'", value))
+                }
+            }
+        }
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn includes_message() {
+            let mut args = args!(Occur::Req, None);
+            args.parse(&vec!("-o", "slow"));
+
+            let err = args.value_of::<Mode>("option").unwrap_err();
+            assert_eq!("option: unable to parse 'slow': unknown mode 'slow'", err.to_string());
+            assert_eq!("unknown mode 'slow'", err.source().unwrap().to_string());
+        }
+    }
+
+    mod no_value {
+        use {Args,ErrorKind};
+
+        #[test]
+        fn has_kind_and_opt_name() {
+            let err = args!().value_of::<i32>("missing").unwrap_err();
+            assert_eq!(ErrorKind::NoValue, err.kind());
+            assert_eq!(Some("missing"), err.opt_name());
+        }
+    }
+
+    mod validation {
+        use std::error::Error;
+        use {Args,ErrorKind};
+        use getopts::Occur;
+        use validations::{Order,OrderValidation,Validation};
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn has_kind_opt_name_and_value() {
+            let mut args = args!(Occur::Req, None);
+            args.parse(&vec!("-o", "5"));
+
+            let lt_1: Box<dyn Validation<T=i32>> = Box::new(OrderValidation::new(Order::LessThan, 1));
+            let err = args.validated_value_of::<i32>("option", &[lt_1]).unwrap_err();
+            assert_eq!(ErrorKind::Validation, err.kind());
+            assert_eq!(Some("option"), err.opt_name());
+            assert_eq!(Some("5"), err.value());
+            assert_eq!("option: invalid value '5': order invalid: 5 is not less than 1", err.to_string());
+        }
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn chains_validation_error() {
+            let mut args = args!(Occur::Req, None);
+            args.parse(&vec!("-o", "5"));
+
+            let lt_1: Box<dyn Validation<T=i32>> = Box::new(OrderValidation::new(Order::LessThan, 1));
+            let err = args.validated_value_of::<i32>("option", &[lt_1]).unwrap_err();
+            let source = err.source().unwrap();
+            assert_eq!("order invalid: 5 is not less than 1", source.to_string());
+            assert!(source.source().is_none());
+        }
+    }
+
+    mod conflict {
+        use {Args,ErrorKind};
+        use getopts::Occur;
+
+        #[test]
+        fn has_kind_and_opt_name() {
+            let mut args = args!(Occur::Optional, None);
+            args.flag("f", "flag", "Flag");
+            args.conflicts_with("flag", "option");
+
+            let err = args.parse(&vec!("-f", "-o", "value")).unwrap_err();
+            assert_eq!(ErrorKind::Conflict, err.kind());
+            assert_eq!(Some("flag"), err.opt_name());
+        }
+    }
+
+    mod unrecognized_command {
+        use {Args,ErrorKind};
+
+        #[test]
+        fn has_kind_and_value() {
+            let mut args = Args::new("program", "Run this program");
+            args.subcommand("build", "Build the project");
+
+            let err = args.parse(&vec!("biuld")).unwrap_err();
+            assert_eq!(ErrorKind::UnrecognizedCommand, err.kind());
+            assert_eq!(Some("biuld"), err.value());
+        }
+    }
+
+    mod other {
+        use {ArgsError,ErrorKind};
+
+        #[test]
+        fn is_default_kind() {
+            let err = ArgsError::new("scope", "message");
+            assert_eq!(ErrorKind::Other, err.kind());
+            assert_eq!(None, err.usage());
+            assert_eq!("scope: message", err.to_string());
+        }
+
+        #[test]
+        fn displays_usage() {
+            let err = ArgsError::new_with_usage("", "message", "Usage: program");
+            assert_eq!(Some("Usage: program"), err.usage());
+            assert_eq!("message\n\nUsage: program", err.to_string());
+        }
+    }
+}
+
+mod groups {
+    use Args;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.flag("v", "verbose", "Verbose");
+        args.option("f", "file", "Read from a file", "FILE", Occur::Optional, None);
+        args.option("u", "url", "Read from a URL", "URL", Occur::Optional, None);
+        args.flag("s", "stdin", "Read from stdin");
+        args
+    }
+
+    mod exactly_one {
+        use super::args;
+
+        #[test]
+        fn returns_err_when_none_given() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "url", "stdin"]);
+
+            let err = args.parse(&vec!("-v")).unwrap_err();
+            assert_eq!("parse: Group 'source' requires one of 'file', 'url', 'stdin'", err.to_string());
+        }
+
+        #[test]
+        fn returns_err_when_many_given() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "url", "stdin"]);
+
+            let err = args.parse(&vec!("-f", "in.txt", "-s")).unwrap_err();
+            assert_eq!("parse: Group 'source' allows only one of 'file', 'url', 'stdin'", err.to_string());
+        }
+
+        #[test]
+        fn returns_ok_when_one_given() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "url", "stdin"]);
+
+            assert!(args.parse(&vec!("-u", "http://example.com")).is_ok());
+        }
+    }
+
+    mod at_most_one {
+        use super::args;
+
+        #[test]
+        fn returns_ok_when_none_given() {
+            let mut args = args();
+            args.at_most_one("source", &["file", "url", "stdin"]);
+
+            assert!(args.parse(&vec!("")).is_ok());
+        }
+
+        #[test]
+        fn returns_err_when_many_given() {
+            let mut args = args();
+            args.at_most_one("source", &["file", "url", "stdin"]);
+
+            assert!(args.parse(&vec!("-u", "http://example.com", "-s")).is_err());
+        }
+    }
+
+    mod at_least_one {
+        use super::args;
+
+        #[test]
+        fn returns_ok_when_many_given() {
+            let mut args = args();
+            args.at_least_one("source", &["file", "url", "stdin"]);
+
+            assert!(args.parse(&vec!("-f", "in.txt", "-s")).is_ok());
+        }
+    }
+
+    mod short_usage {
+        use super::args;
+
+        #[test]
+        fn renders_members_together() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "url", "stdin"]);
+
+            assert_eq!("Usage: program [-v] (--file FILE | --url URL | --stdin)", args.short_usage());
+        }
+
+        #[test]
+        fn renders_overlapping_groups() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "url"]);
+            args.at_most_one("local", &["file", "stdin"]);
+
+            assert_eq!("Usage: program [-v] (--file FILE | --url URL) [--file FILE | --stdin]", args.short_usage());
+        }
+    }
+
+    mod overlapping {
+        use super::args;
+
+        #[test]
+        fn checks_each_group() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "url"]);
+            args.at_most_one("local", &["file", "stdin"]);
+
+            assert!(args.parse(&vec!("-f", "in.txt")).is_ok());
+            let err = args.parse(&vec!("-f", "in.txt", "-s")).unwrap_err();
+            assert_eq!("parse: Group 'local' allows only one of 'file', 'stdin'", err.to_string());
+        }
+    }
+
+    mod unregistered {
+        use super::args;
+
+        #[test]
+        fn is_ignored() {
+            let mut args = args();
+            args.exactly_one("source", &["file", "missing"]);
+
+            assert!(args.parse(&vec!("")).is_ok());
+        }
+    }
+}
+
 mod has_options {
     mod has_none {
         use Args;
 
         #[test]
-        fn it_returns_false() {
-            let args = Args::new("program", "Run this program.");
+        fn it_returns_false() {
+            let args = Args::new("program", "Run this program.");
+
+            assert!(!args.has_options());
+        }
+    }
+
+    mod has_flag {
+        use Args;
+
+        #[test]
+        fn it_returns_true() {
+            let args = args!();
+
+            assert!(args.has_options());
+        }
+    }
+
+    mod has_option {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn it_returns_true() {
+            let args = args!(Occur::Optional, None);
+
+            assert!(args.has_options());
+        }
+    }
+}
+
+mod has_value {
+    mod absent {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_false() {
+            let mut args = args!(Occur::Optional, None);
+            args.parse(&vec!(""));
+
+            assert!(!args.has_value("option"));
+        }
+    }
+
+    mod present {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_true() {
+            let mut args = args!(Occur::Optional, None);
+            args.parse(&vec!("-o", "option"));
+
+            assert!(args.has_value("option"));
+        }
+    }
+}
+
+mod parse {
+    mod unrecognized_option {
+        use Args;
+
+        #[test]
+        fn returns_err() {
+            let mut args = args!();
+            assert!(args.parse(&vec!("-i")).is_err());
+        }
+    }
+
+    mod flag {
+        mod absent {
+            use Args;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_false() {
+                let mut args = args!();
+                args.parse(&vec!(""));
+
+                assert!(!args.value_of::<bool>("flag").unwrap());
+            }
+        }
+
+        mod present {
+            use Args;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_true() {
+                let mut args = args!();
+                args.parse(&vec!("-f"));
+
+                assert!(args.value_of::<bool>("flag").unwrap());
+            }
+        }
+    }
+
+    mod positional {
+        mod required {
+            mod absent {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                fn returns_err() {
+                    let mut args = args!();
+                    args.positional("file", "File", "FILE", Occur::Req);
+                    assert!(args.parse(&vec!("-f")).is_err());
+                }
+            }
+
+            mod present {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                fn returns_ok() {
+                    let mut args = args!();
+                    args.positional("file", "File", "FILE", Occur::Req);
+                    assert!(args.parse(&vec!("-f", "a.txt")).is_ok());
+                }
+            }
+        }
+
+        mod unexpected {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            fn returns_err() {
+                let mut args = args!();
+                args.positional("file", "File", "FILE", Occur::Optional);
+                assert!(args.parse(&vec!("a.txt", "b.txt")).is_err());
+            }
+        }
+
+        mod undeclared {
+            use Args;
+
+            #[test]
+            fn returns_ok() {
+                let mut args = args!();
+                assert!(args.parse(&vec!("a.txt")).is_ok());
+            }
+        }
+    }
+
+    mod response_files {
+        mod disabled {
+            use Args;
+
+            #[test]
+            fn leaves_argument_untouched() {
+                let mut args = args!();
+                assert!(args.parse(&vec!("@/nonexistent/args.rsp")).is_ok());
+                assert!(!args.value_of::<bool>("flag").unwrap());
+            }
+        }
+
+        mod enabled {
+            use Args;
+            use getopts::Occur;
+            use tst::TempDir;
+
+            #[test]
+            fn expands_file() {
+                let dir = TempDir::new();
+                let path = dir.write("args.rsp", "-f\n-o 'a b'\n");
+                let mut args = args!(Occur::Optional, None);
+                args.flag("f", "flag", "Flag").response_files(true);
+
+                assert!(args.parse(vec!(format!("@{}", path.display()))).is_ok());
+                assert!(args.value_of::<bool>("flag").unwrap());
+                assert_eq!("a b".to_string(), args.value_of::<String>("option").unwrap());
+            }
+        }
+    }
+
+    mod counted_flag {
+        mod absent {
+            use Args;
+
+            #[test]
+            fn returns_zero() {
+                let mut args = Args::new("program", "Run this program");
+                args.counted_flag("v", "verbose", "Verbosity", None, None);
+
+                assert!(args.parse(&vec!("")).is_ok());
+                assert_eq!(0u8, args.value_of::<u8>("verbose").unwrap());
+            }
+        }
+
+        mod present {
+            use Args;
+
+            #[test]
+            fn returns_occurrences() {
+                let mut args = Args::new("program", "Run this program");
+                args.counted_flag("v", "verbose", "Verbosity", None, None);
+
+                assert!(args.parse(&vec!("-vvv")).is_ok());
+                assert_eq!(3u8, args.value_of::<u8>("verbose").unwrap());
+            }
+        }
+
+        mod capped {
+            use Args;
+
+            #[test]
+            fn returns_max() {
+                let mut args = Args::new("program", "Run this program");
+                args.counted_flag("v", "verbose", "Verbosity", None, Some(2));
+
+                assert!(args.parse(&vec!("-v", "-vv")).is_ok());
+                assert_eq!(2u8, args.value_of::<u8>("verbose").unwrap());
+                assert!(args.usage().contains("Verbosity [repeatable, max: 2]"));
+            }
+        }
+
+        mod floored {
+            use Args;
+
+            #[test]
+            fn returns_min_if_absent() {
+                let mut args = Args::new("program", "Run this program");
+                args.counted_flag("v", "verbose", "Verbosity", Some(1), Some(3));
+
+                assert!(args.parse(&Vec::<&str>::new()).is_ok());
+                assert_eq!(1u8, args.value_of::<u8>("verbose").unwrap());
+                assert!(args.usage().contains("Verbosity [repeatable, min: 1, max: 3]"));
+            }
+        }
+    }
+
+    mod negatable_flag {
+        mod negated {
+            use Args;
+
+            #[test]
+            fn returns_false() {
+                let mut args = Args::new("program", "Run this program");
+                args.negatable_flag("c", "color", "Colorize output", true);
+
+                assert!(args.parse(&vec!("--no-color")).is_ok());
+                assert!(!args.value_of::<bool>("color").unwrap());
+            }
+        }
+
+        mod usage {
+            use Args;
+
+            #[test]
+            fn shows_both_forms() {
+                let mut args = Args::new("program", "Run this program");
+                args.negatable_flag("c", "color", "Colorize output", true);
+
+                assert_eq!("Usage: program [--[no-]color]", args.short_usage());
+                assert!(args.usage().contains("-c, --[no-]color    Colorize output [default: true]"));
+                assert!(!args.usage().contains("--no-color"));
+            }
+        }
+    }
+
+    mod single {
+        mod argument_missing {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            fn returns_err() {
+                let mut args = args!(Occur::Optional, None);
+                assert!(args.parse(&vec!("-o")).is_err());
+            }
+        }
+
+        mod optional {
+            mod absent {
+                mod defaulted {
+                    use Args;
+                    use getopts::Occur;
+
+                    #[test]
+                    #[allow(unused_must_use)]
+                    fn returns_default() {
+                        let default = "default";
+                        let mut args = args!(Occur::Optional, Some(default.to_string()));
+                        args.parse(&vec!(""));
+
+                        assert_eq!(default.to_string(), args.value_of::<String>("option").unwrap());
+                    }
+                }
+
+                mod not_defaulted {
+                    use Args;
+                    use getopts::Occur;
+
+                    #[test]
+                    #[allow(unused_must_use)]
+                    fn returns_err() {
+                        let mut args = args!(Occur::Optional, None);
+                        args.parse(&vec!(""));
+
+                        assert!(args.value_of::<String>("option").is_err());
+                    }
+                }
+            }
+
+            mod present {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                #[allow(unused_must_use)]
+                fn returns_value() {
+                    let value = "value";
+                    let mut args = args!(Occur::Optional, None);
+                    args.parse(&vec!("-o", value));
+
+                    assert_eq!(value.to_string(), args.value_of::<String>("option").unwrap());
+                }
+            }
+        }
+
+        mod required {
+            mod absent {
+                mod defaulted {
+                    use Args;
+                    use getopts::Occur;
+
+                    #[test]
+                    #[allow(unused_must_use)]
+                    fn returns_default() {
+                        let default = "default";
+                        let mut args = args!(Occur::Req, Some(default.to_string()));
+                        args.parse(&vec!(""));
+
+                        assert_eq!(default.to_string(), args.value_of::<String>("option").unwrap());
+                    }
+                }
+
+                mod not_defaulted {
+                    use Args;
+                    use getopts::Occur;
+
+                    #[test]
+                    fn returns_err() {
+                        let mut args = args!(Occur::Req, None);
+                        assert!(args.parse(&vec!("")).is_err());
+                    }
+                }
+            }
+
+            mod present {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                #[allow(unused_must_use)]
+                fn returns_value() {
+                    let value = "value";
+                    let mut args = args!(Occur::Req, None);
+                    args.parse(&vec!("-o", value));
+
+                    assert_eq!(value.to_string(), args.value_of::<String>("option").unwrap());
+                }
+            }
+        }
+    }
+
+    mod multi {
+        mod absent {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            fn returns_ok() {
+                let raw_args: Vec<String> = Vec::new();
+                let mut args = args!(Occur::Multi, None);
+                let parse = args.parse(&raw_args);
+                assert!(parse.is_ok(), "{}", parse.unwrap_err());
+            }
+        }
+
+        mod argument_missing {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            fn returns_err() {
+                let mut args = args!(Occur::Multi, None);
+                assert!(args.parse(&vec!("-o")).is_err());
+            }
+        }
+
+        mod present {
+            mod single_arg {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                #[allow(unused_must_use)]
+                fn returns_value() {
+                    let value = "value";
+                    let mut args = args!(Occur::Multi, None);
+                    args.parse(&vec!("-o", value));
+
+                    let result = args.values_of::<String>("option");
+                    assert!(result.is_ok());
+                    let results = result.unwrap();
+                    assert_eq!(1, results.len());
+                    assert_eq!(value, results[0]);
+                }
+            }
+
+            mod value_with_separator {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                #[allow(unused_must_use)]
+                fn returns_value_intact() {
+                    let mut args = args!(Occur::Multi, None);
+                    args.parse(&vec!("-o", "a,b", "-o", "c"));
+
+                    let results = args.values_of::<String>("option").unwrap();
+                    assert_eq!(vec!("a,b".to_string(), "c".to_string()), results);
+                }
+            }
+
+            mod delimited {
+                use Args;
+
+                #[test]
+                #[allow(unused_must_use)]
+                fn returns_split_values() {
+                    let mut args = Args::new("program", "Run this program");
+                    args.delimited_option("t", "tags", "Tags", "TAGS", ",");
+                    args.parse(&vec!("-t", "a,b", "-t", "c"));
+
+                    let results = args.values_of::<String>("tags").unwrap();
+                    assert_eq!(vec!("a".to_string(), "b".to_string(), "c".to_string()), results);
+                }
+            }
+
+            mod multiple_args {
+                use Args;
+                use getopts::Occur;
+
+                #[test]
+                #[allow(unused_must_use)]
+                fn returns_values() {
+                    let values = ["test", "value"];
+                    let mut args = args!(Occur::Multi, None);
+                    args.parse(&vec!("-o", values[0], "-o", values[1]));
+
+                    let result = args.values_of::<String>("option");
+                    assert!(result.is_ok());
+                    let results = result.unwrap();
+                    assert_eq!(2, values.len());
+                    assert_eq!(values[0], results[0]);
+                    assert_eq!(values[1], results[1]);
+                }
+            }
+        }
+    }
+}
+
+mod possible_values {
+    mod allowed {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn returns_ok() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("m", "mode", "Mode", "MODE", Occur::Optional, None);
+            args.possible_values("mode", &["fast", "slow"]);
+
+            assert!(args.parse(&vec!("-m", "slow")).is_ok());
+        }
+    }
+
+    mod disallowed {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn returns_err() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("m", "mode", "Mode", "MODE", Occur::Optional, None);
+            args.possible_values("mode", &["fast", "slow"]);
+
+            let err = args.parse(&vec!("-m", "medium")).unwrap_err();
+            assert_eq!("parse: Invalid value 'medium' for option 'mode' (possible values: fast, slow)", err.to_string());
+        }
+    }
+
+    mod usage {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        fn lists_values() {
+            let mut args = Args::new("program", "Run this program");
+            args.option("m", "mode", "Mode", "MODE", Occur::Optional, None);
+            args.possible_values("mode", &["fast", "slow"]);
+
+            assert!(args.usage().contains("Mode [possible values: fast, slow]"));
+        }
+    }
+}
+
+mod positional_of {
+    mod absent {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_err() {
+            let mut args = args!();
+            args.positional("file", "File", "FILE", Occur::Optional);
+            args.parse(&vec!("-f"));
+
+            assert!(args.positional_of::<String>("file").is_err());
+        }
+    }
+
+    mod present {
+        mod cannot_be_cast {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_err() {
+                let mut args = args!();
+                args.positional("count", "Count", "N", Occur::Req);
+                args.parse(&vec!("value"));
+
+                assert!(args.positional_of::<i32>("count").is_err());
+            }
+        }
+
+        mod can_be_cast {
+            use Args;
+            use getopts::Occur;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_ok_value() {
+                let mut args = args!();
+                args.positional("count", "Count", "N", Occur::Req);
+                args.parse(&vec!("-f", "5"));
+
+                assert_eq!(5i32, args.positional_of::<i32>("count").unwrap());
+            }
+        }
+    }
+}
+
+mod positionals_of {
+    mod unregistered {
+        use Args;
+
+        #[test]
+        fn returns_err() {
+            assert!(args!().positionals_of::<String>("files").is_err());
+        }
+    }
 
-            assert!(!args.has_options());
+    mod absent {
+        use Args;
+        use getopts::Occur;
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_empty() {
+            let mut args = args!();
+            args.positional("files", "Files", "FILE", Occur::Multi);
+            args.parse(&vec!("-f"));
+
+            assert!(args.positionals_of::<String>("files").unwrap().is_empty());
         }
     }
 
-    mod has_flag {
+    mod present {
         use Args;
+        use getopts::Occur;
 
         #[test]
-        fn it_returns_true() {
-            let args = args!();
+        #[allow(unused_must_use)]
+        fn returns_values() {
+            let mut args = args!();
+            args.positional("first", "First", "FIRST", Occur::Req);
+            args.positional("rest", "Rest", "REST", Occur::Multi);
+            args.parse(&vec!("a.txt", "-f", "b.txt", "c.txt"));
 
-            assert!(args.has_options());
+            assert_eq!(vec!("a.txt".to_string()), args.positionals_of::<String>("first").unwrap());
+            assert_eq!(vec!("b.txt".to_string(), "c.txt".to_string()),
+                args.positionals_of::<String>("rest").unwrap());
         }
     }
+}
 
-    mod has_option {
+mod report {
+    mod usage_error {
+        use {Args,Stream,EX_USAGE};
+
+        #[test]
+        fn returns_usage_code_on_stderr() {
+            let err = args!().parse(&vec!("--bad")).unwrap_err();
+            assert_eq!(EX_USAGE, err.exit_code());
+            assert_eq!((EX_USAGE, Stream::Stderr, r#"parse: Unrecognized option: 'bad'
+  program --bad
+          ^^^^^"#.to_string()), err.report());
+        }
+    }
+
+    mod config_error {
+        use {ArgsError,ErrorKind,Stream,EX_CONFIG};
+
+        #[test]
+        fn returns_config_code_on_stderr() {
+            let err = ArgsError::new("args.ini:2", "unknown key 'flga'").with_kind(ErrorKind::Config);
+            assert_eq!((EX_CONFIG, Stream::Stderr, "args.ini:2: unknown key 'flga'".to_string()), err.report());
+        }
+    }
+
+    mod help {
+        use {Args,Stream,EX_OK};
+
+        #[test]
+        fn returns_success_code_on_stdout() {
+            let mut args = args!();
+            args.help_flag("h", "help", "Print the usage menu");
+
+            let (code, stream, text) = args.parse(&vec!("-h")).unwrap().report();
+            assert_eq!((EX_OK, Stream::Stdout), (code, stream));
+            assert_eq!(args.full_usage().trim_end(), text);
+        }
+    }
+
+    mod version {
+        use {Args,Stream};
+
+        #[test]
+        fn returns_success_code_on_stdout() {
+            let mut args = args!();
+            args.version_flag("V", "version", "Print the version", "1.2.3");
+
+            let report = args.parse(&vec!("-V")).unwrap().report();
+            assert_eq!((0, Stream::Stdout, "program 1.2.3".to_string()), report);
+        }
+    }
+
+    mod parsed {
+        use {Args,Stream};
+
+        #[test]
+        fn returns_success_code_without_text() {
+            let report = args!().parse(&vec!("-f")).unwrap().report();
+            assert_eq!((0, Stream::Stdout, String::new()), report);
+        }
+    }
+}
+
+mod reference {
+    use Args;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.option("i", "iter", "The number of times to run", "TIMES", Occur::Req, None);
+        args.option("l", "log_file", "The name of the log file", "NAME", Occur::Optional, Some("output.log".to_string()));
+        args
+    }
+
+    mod markdown {
+        use super::args;
+
+        #[test]
+        fn anchors_options() {
+            assert!(args().markdown_reference().contains("<a id=\"option-iter\"></a>\n### `-i, --iter TIMES`\n"));
+        }
+
+        #[test]
+        fn includes_required_and_default() {
+            let markdown = args().markdown_reference();
+
+            assert!(markdown.contains("The number of times to run\n\n- Required: yes\n"));
+            assert!(markdown.contains("The name of the log file\n\n- Default: `output.log`\n"));
+        }
+    }
+
+    mod html {
+        use super::args;
+
+        #[test]
+        fn anchors_options() {
+            assert!(args().html_reference().contains("<dt id=\"option-log_file\">"));
+        }
+
+        #[test]
+        fn is_standalone() {
+            let html = args().html_reference();
+
+            assert!(html.starts_with("<!DOCTYPE html>\n"));
+            assert!(html.ends_with("</html>\n"));
+        }
+    }
+}
+
+mod rules {
+    use Args;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.flag("s", "stdin", "Read from stdin");
+        args.option("i", "input", "Input file", "FILE", Occur::Optional, None);
+        args.option("m", "mode", "Mode", "MODE", Occur::Optional, Some("fast".to_string()));
+        args.option("k", "key", "Key file", "KEY", Occur::Optional, None);
+        args
+    }
+
+    mod conflicts_with {
+        use super::args;
+
+        #[test]
+        fn returns_err_when_both_given() {
+            let mut args = args();
+            args.conflicts_with("input", "stdin");
+
+            let err = args.parse(&vec!("-i", "in.txt", "-s")).unwrap_err();
+            assert_eq!("parse: Option 'input' cannot be used with 'stdin'", err.to_string());
+        }
+
+        #[test]
+        fn returns_ok_when_one_given() {
+            let mut args = args();
+            args.conflicts_with("input", "stdin");
+
+            assert!(args.parse(&vec!("-s")).is_ok());
+        }
+
+        #[test]
+        fn ignores_defaults() {
+            let mut args = args();
+            args.conflicts_with("mode", "stdin");
+
+            assert!(args.parse(&vec!("-s")).is_ok());
+        }
+
+        #[test]
+        fn returns_err_when_value_is_false() {
+            let mut args = args();
+            args.conflicts_with("input", "stdin");
+
+            let err = args.parse(&vec!("--input", "false", "--stdin")).unwrap_err();
+            assert_eq!("parse: Option 'input' cannot be used with 'stdin'", err.to_string());
+        }
+
+        #[test]
+        fn ignores_flags_switched_off() {
+            let mut args = args();
+            args.conflicts_with("input", "stdin");
+            args.env("stdin", "ARGS_TEST_STDIN_OFF");
+            args.env_lookup(|name| if name == "ARGS_TEST_STDIN_OFF" { Some("false".to_string()) } else { None });
+
+            assert!(args.parse(&vec!("-i", "in.txt")).is_ok());
+        }
+
+        #[test]
+        fn annotates_usage() {
+            let mut args = args();
+            args.conflicts_with("input", "stdin");
+
+            let usage = args.full_usage();
+            assert!(usage.contains("Input file [conflicts with: --stdin]"));
+            assert!(usage.contains("Read from stdin [conflicts with: --input]"));
+        }
+    }
+
+    mod requires {
+        use super::args;
+
+        #[test]
+        fn returns_err_when_other_absent() {
+            let mut args = args();
+            args.requires("key", "input");
+
+            let err = args.parse(&vec!("-k", "key.pem")).unwrap_err();
+            assert_eq!("parse: Option 'key' requires 'input'", err.to_string());
+        }
+
+        #[test]
+        fn returns_ok_when_other_is_false() {
+            let mut args = args();
+            args.requires("key", "input");
+
+            assert!(args.parse(&vec!("-k", "key.pem", "-i", "false")).is_ok());
+        }
+
+        #[test]
+        fn returns_ok_when_other_given() {
+            let mut args = args();
+            args.requires("key", "input");
+
+            assert!(args.parse(&vec!("-k", "key.pem", "-i", "in.txt")).is_ok());
+        }
+    }
+
+    mod required_if_eq {
+        use super::args;
+
+        #[test]
+        fn returns_err_when_value_matches() {
+            let mut args = args();
+            args.required_if_eq("key", "mode", "secure");
+
+            let err = args.parse(&vec!("-m", "secure")).unwrap_err();
+            assert_eq!("parse: Option 'key' is required when 'mode' is 'secure'", err.to_string());
+        }
+
+        #[test]
+        fn returns_ok_when_value_differs() {
+            let mut args = args();
+            args.required_if_eq("key", "mode", "secure");
+
+            assert!(args.parse(&vec!("")).is_ok());
+        }
+    }
+
+    mod required_unless_present {
+        use super::args;
+
+        #[test]
+        fn returns_err_when_neither_given() {
+            let mut args = args();
+            args.required_unless_present("input", "stdin");
+
+            let err = args.parse(&vec!("")).unwrap_err();
+            assert_eq!("parse: Option 'input' is required unless 'stdin' is present", err.to_string());
+        }
+
+        #[test]
+        fn returns_ok_when_other_given() {
+            let mut args = args();
+            args.required_unless_present("input", "stdin");
+
+            assert!(args.parse(&vec!("-s")).is_ok());
+        }
+    }
+
+    mod unregistered {
+        use super::args;
+
+        #[test]
+        fn is_ignored() {
+            let mut args = args();
+            args.conflicts_with("input", "missing");
+
+            assert!(args.parse(&vec!("-i", "in.txt")).is_ok());
+        }
+    }
+}
+
+mod short_usage {
+    mod with_positionals {
         use Args;
         use getopts::Occur;
 
         #[test]
-        fn it_returns_true() {
-            let args = args!(Occur::Optional, None);
+        fn includes_hints() {
+            let mut args = args!();
+            args.positional("input", "Input", "IN", Occur::Req);
+            args.positional("output", "Output", "OUT", Occur::Optional);
 
-            assert!(args.has_options());
+            assert_eq!("Usage: program [-f] IN [OUT]", args.short_usage());
         }
     }
 }
 
-mod has_value {
-    mod absent {
+mod usage {
+    mod with_positionals {
         use Args;
         use getopts::Occur;
 
         #[test]
-        #[allow(unused_must_use)]
-        fn returns_false() {
-            let mut args = args!(Occur::Optional, None);
-            args.parse(&vec!(""));
+        fn includes_arguments() {
+            let mut args = Args::new("program", "Run this program");
+            args.positional("files", "The files to process", "FILE", Occur::Multi);
 
-            assert!(!args.has_value("option"));
+            assert_eq!("Run this program\n\nArguments:\n    FILE                The files to process\n",
+                args.usage());
         }
     }
 
-    mod present {
+    mod with_max_width {
+        use Args;
+
+        #[test]
+        fn wraps_descriptions() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("f", "flag", "A flag with a description too long to fit");
+            args.max_width(54);
+
+            assert_eq!("Run this program\n\nOptions:\n    -f, --flag          A flag with a description too\n                        long to fit\n",
+                args.usage());
+        }
+    }
+
+    mod with_columns {
+        use Args;
+
+        #[test]
+        fn narrows_to_terminal() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("f", "flag", "A flag with a description too long to fit");
+            args.env_lookup(|name| if name == "COLUMNS" { Some("54".to_string()) } else { None });
+
+            assert!(args.usage().contains("too\n                        long to fit\n"));
+        }
+
+        #[test]
+        fn never_exceeds_max_width() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("f", "flag", "A flag with a description too long to fit");
+            args.max_width(54);
+            args.env_lookup(|name| if name == "COLUMNS" { Some("200".to_string()) } else { None });
+
+            assert!(args.usage().contains("too\n                        long to fit\n"));
+        }
+    }
+
+    mod with_headings {
         use Args;
         use getopts::Occur;
 
         #[test]
-        #[allow(unused_must_use)]
-        fn returns_true() {
-            let mut args = args!(Occur::Optional, None);
-            args.parse(&vec!("-o", "option"));
+        fn groups_options_under_headings() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("v", "verbose", "Verbose");
+            args.heading("Input");
+            args.option("i", "input", "Input file", "FILE", Occur::Optional, None);
+            args.heading("Output");
+            args.option("o", "output", "Output file", "FILE", Occur::Optional, None);
+            args.heading("");
+            args.flag("q", "quiet", "Quiet");
+
+            assert_eq!("Run this program\n\
+                \nOptions:\n    -v, --verbose       Verbose\n    -q, --quiet         Quiet\n\
+                \nInput:\n    -i, --input FILE    Input file\n\
+                \nOutput:\n    -o, --output FILE   Output file\n",
+                args.usage());
+        }
+    }
 
-            assert!(args.has_value("option"));
+    mod with_help_text {
+        use Args;
+
+        #[test]
+        fn surrounds_sections() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("f", "flag", "Flag");
+            args.before_help("Reads input from the network.");
+            args.after_help("Examples:\n    program -f\n");
+
+            assert_eq!("Run this program\n\
+                \nReads input from the network.\n\
+                \nOptions:\n    -f, --flag          Flag\n\
+                \nExamples:\n    program -f\n",
+                args.usage());
+        }
+    }
+
+    mod with_long_only_options {
+        use Args;
+
+        #[test]
+        fn aligns_long_names() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("f", "flag", "Flag");
+            args.negatable_flag("", "color", "Color", true);
+
+            assert_eq!("Run this program\n\nOptions:\n    -f, --flag          Flag\n        --[no-]color    Color [default: true]\n",
+                args.usage());
         }
     }
 }
 
-mod parse {
-    mod unrecognized_option {
+mod subcommand {
+    mod absent {
+        use Args;
+
+        #[test]
+        fn returns_none() {
+            let mut args = args!();
+            args.subcommand("build", "Build the project");
+            assert!(args.parse(&vec!("-f")).is_ok());
+
+            assert!(args.subcommand_name().is_none());
+            assert!(args.subcommand_args().is_none());
+        }
+    }
+
+    mod present {
+        use Args;
+
+        #[test]
+        fn dispatches_remaining_args() {
+            let mut args = args!();
+            args.subcommand("build", "Build the project").flag("r", "release", "Release");
+            assert!(args.parse(&vec!("-f", "build", "-r")).is_ok());
+
+            assert!(args.value_of::<bool>("flag").unwrap());
+            assert_eq!(Some("build"), args.subcommand_name());
+            assert!(args.subcommand_args().unwrap().value_of::<bool>("release").unwrap());
+        }
+    }
+
+    mod unrecognized {
         use Args;
 
         #[test]
         fn returns_err() {
             let mut args = args!();
-            assert!(args.parse(&vec!("-i")).is_err());
+            args.subcommand("build", "Build the project");
+            assert!(args.parse(&vec!("test")).is_err());
         }
     }
 
-    mod flag {
-        mod absent {
-            use Args;
+    mod help_command {
+        use {Args,ParseOutcome};
+
+        #[test]
+        fn requests_subcommand_usage() {
+            let mut args = args!();
+            let usage = args.subcommand("build", "Build the project").full_usage();
+
+            assert_eq!(ParseOutcome::Help(usage), args.parse(&vec!("help", "build")).unwrap());
+        }
+    }
+
+    mod help_flag {
+        use {Args,ParseOutcome};
+        use getopts::Occur;
+
+        #[test]
+        fn requests_subcommand_usage() {
+            let mut args = args!();
+            let usage = args.subcommand("build", "Build the project")
+                .option("t", "target", "Target", "TARGET", Occur::Req, None)
+                .full_usage();
+            let outcome = args.parse(&vec!("build", "--help")).unwrap();
 
-            #[test]
-            #[allow(unused_must_use)]
-            fn returns_false() {
-                let mut args = args!();
-                args.parse(&vec!(""));
+            assert_eq!(Some("build"), args.subcommand_name());
+            assert_eq!(ParseOutcome::Help(usage), outcome);
+        }
+    }
 
-                assert!(!args.value_of::<bool>("flag").unwrap());
-            }
+    mod usage {
+        use Args;
+
+        #[test]
+        fn lists_subcommands() {
+            let mut args = Args::new("program", "Run this program");
+            args.subcommand("build", "Build the project");
+            args.subcommand("test", "Test the project");
+
+            assert_eq!("Usage: program COMMAND [ARGS]..", args.short_usage());
+            assert_eq!("Run this program\n\nCommands:\n    build               Build the project\n    \
+                test                Test the project\n", args.usage());
         }
+    }
 
-        mod present {
-            use Args;
+    mod subcommand_usage {
+        use Args;
 
-            #[test]
-            #[allow(unused_must_use)]
-            fn returns_true() {
-                let mut args = args!();
-                args.parse(&vec!("-f"));
+        #[test]
+        fn includes_program_name() {
+            let mut args = Args::new("program", "Run this program");
+            let build = args.subcommand("build", "Build the project");
+            build.flag("r", "release", "Release");
 
-                assert!(args.value_of::<bool>("flag").unwrap());
-            }
+            assert_eq!("Usage: program build [-r]", build.short_usage());
         }
     }
+}
 
-    mod single {
-        mod argument_missing {
-            use Args;
-            use getopts::Occur;
+mod suggestions {
+    mod unrecognized_option {
+        use Args;
 
-            #[test]
-            fn returns_err() {
-                let mut args = args!(Occur::Optional, None);
-                assert!(args.parse(&vec!("-o")).is_err());
-            }
+        #[test]
+        fn suggests_closest_long_name() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("v", "verbose", "Verbose");
+            args.flag("q", "quiet", "Quiet");
+
+            let err = args.parse(&vec!("--verbsoe")).unwrap_err();
+            assert_eq!("parse: Unrecognized option: 'verbsoe'. Did you mean '--verbose'?", err.to_string());
         }
 
-        mod optional {
-            mod absent {
-                mod defaulted {
-                    use Args;
-                    use getopts::Occur;
+        #[test]
+        fn suggests_aliases() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("c", "color", "Color");
+            args.alias("color", "colour");
 
-                    #[test]
-                    #[allow(unused_must_use)]
-                    fn returns_default() {
-                        let default = "default";
-                        let mut args = args!(Occur::Optional, Some(default.to_string()));
-                        args.parse(&vec!(""));
+            let err = args.parse(&vec!("--colours")).unwrap_err();
+            assert_eq!("parse: Unrecognized option: 'colours'. Did you mean '--colour'?", err.to_string());
+        }
 
-                        assert_eq!(default.to_string(), args.value_of::<String>("option").unwrap());
-                    }
-                }
+        #[test]
+        fn suggests_negated_names() {
+            let mut args = Args::new("program", "Run this program");
+            args.negatable_flag("", "color", "Color", true);
 
-                mod not_defaulted {
-                    use Args;
-                    use getopts::Occur;
+            let err = args.parse(&vec!("--no-colr")).unwrap_err();
+            assert_eq!("parse: Unrecognized option: 'no-colr'. Did you mean '--no-color'?", err.to_string());
+        }
 
-                    #[test]
-                    #[allow(unused_must_use)]
-                    fn returns_err() {
-                        let mut args = args!(Occur::Optional, None);
-                        args.parse(&vec!(""));
+        #[test]
+        fn omits_unrelated() {
+            let mut args = Args::new("program", "Run this program");
+            args.flag("v", "verbose", "Verbose");
 
-                        assert!(args.value_of::<String>("option").is_err());
-                    }
-                }
-            }
+            let err = args.parse(&vec!("--output")).unwrap_err();
+            assert_eq!("parse: Unrecognized option: 'output'", err.to_string());
+        }
+    }
 
-            mod present {
-                use Args;
-                use getopts::Occur;
+    mod unrecognized_command {
+        use Args;
 
-                #[test]
-                #[allow(unused_must_use)]
-                fn returns_value() {
-                    let value = "value";
-                    let mut args = args!(Occur::Optional, None);
-                    args.parse(&vec!("-o", value));
+        #[test]
+        fn suggests_closest_command() {
+            let mut args = Args::new("program", "Run this program");
+            args.subcommand("build", "Build the project");
+            args.subcommand("test", "Test the project");
 
-                    assert_eq!(value.to_string(), args.value_of::<String>("option").unwrap());
-                }
-            }
+            let err = args.parse(&vec!("buidl")).unwrap_err();
+            assert_eq!("parse: Unrecognized command: 'buidl'. Did you mean 'build'?", err.to_string());
         }
+    }
+}
 
-        mod required {
-            mod absent {
-                mod defaulted {
-                    use Args;
-                    use getopts::Occur;
+mod help_flag {
+    mod given {
+        use {Args,ParseOutcome};
+        use getopts::Occur;
 
-                    #[test]
-                    #[allow(unused_must_use)]
-                    fn returns_default() {
-                        let default = "default";
-                        let mut args = args!(Occur::Req, Some(default.to_string()));
-                        args.parse(&vec!(""));
+        #[test]
+        fn returns_help_despite_required_options() {
+            let mut args = args!(Occur::Req, None);
+            args.help_flag("h", "help", "Print the usage menu");
+            let usage = args.full_usage();
 
-                        assert_eq!(default.to_string(), args.value_of::<String>("option").unwrap());
-                    }
-                }
+            assert_eq!(ParseOutcome::Help(usage), args.parse(&vec!("--help")).unwrap());
+        }
+    }
 
-                mod not_defaulted {
-                    use Args;
-                    use getopts::Occur;
+    mod absent {
+        use {Args,ParseOutcome};
+        use getopts::Occur;
 
-                    #[test]
-                    fn returns_err() {
-                        let mut args = args!(Occur::Req, None);
-                        assert!(args.parse(&vec!("")).is_err());
-                    }
-                }
-            }
+        #[test]
+        fn returns_parsed() {
+            let mut args = args!(Occur::Req, None);
+            args.help_flag("h", "help", "Print the usage menu");
 
-            mod present {
-                use Args;
-                use getopts::Occur;
+            assert_eq!(ParseOutcome::Parsed, args.parse(&vec!("-o", "value")).unwrap());
+        }
 
-                #[test]
-                #[allow(unused_must_use)]
-                fn returns_value() {
-                    let value = "value";
-                    let mut args = args!(Occur::Req, None);
-                    args.parse(&vec!("-o", value));
+        #[test]
+        fn checks_required_options() {
+            let mut args = args!(Occur::Req, None);
+            args.help_flag("h", "help", "Print the usage menu");
 
-                    assert_eq!(value.to_string(), args.value_of::<String>("option").unwrap());
-                }
-            }
+            assert!(args.parse(&vec!("")).is_err());
         }
     }
 
-    mod multi {
-        mod absent {
-            use Args;
-            use getopts::Occur;
+    mod subcommand {
+        use {Args,ParseOutcome};
 
-            #[test]
-            fn returns_ok() {
-                let raw_args: Vec<String> = Vec::new();
-                let mut args = args!(Occur::Multi, None);
-                let parse = args.parse(&raw_args);
-                assert!(parse.is_ok(), parse.unwrap_err().to_string());
-            }
+        #[test]
+        fn returns_subcommand_help() {
+            let mut args = args!();
+            let usage = args.subcommand("build", "Build the project")
+                .help_flag("h", "help", "Print the usage menu")
+                .full_usage();
+
+            assert_eq!(ParseOutcome::Help(usage), args.parse(&vec!("build", "-h")).unwrap());
         }
+    }
+    mod without_long_name {
+        use {Args,ErrorKind};
 
-        mod argument_missing {
-            use Args;
-            use getopts::Occur;
+        #[test]
+        fn is_ignored() {
+            let mut args = args!();
+            args.help_flag("h", "", "Print the usage menu");
 
-            #[test]
-            fn returns_err() {
-                let mut args = args!(Occur::Multi, None);
-                assert!(args.parse(&vec!("-o")).is_err());
-            }
+            assert!(!args.full_usage().contains("Print the usage menu"));
+            assert_eq!(ErrorKind::UnrecognizedOption, args.parse(&vec!("-h")).unwrap_err().kind());
         }
+    }
+}
 
-        mod present {
-            mod single_arg {
-                use Args;
-                use getopts::Occur;
-
-                #[test]
-                #[allow(unused_must_use)]
-                fn returns_value() {
-                    let value = "value";
-                    let mut args = args!(Occur::Multi, None);
-                    args.parse(&vec!("-o", value));
+mod version_flag {
+    mod given {
+        use {Args,ParseOutcome};
+        use getopts::Occur;
 
-                    let result = args.values_of::<String>("option");
-                    assert!(result.is_ok());
-                    let results = result.unwrap();
-                    assert_eq!(1, results.len());
-                    assert_eq!(value, results[0]);
-                }
-            }
+        #[test]
+        fn returns_version() {
+            let mut args = args!(Occur::Req, None);
+            args.version_flag("V", "version", "Print the version", "1.2.3");
 
-            mod multiple_args {
-                use Args;
-                use getopts::Occur;
+            assert_eq!(ParseOutcome::Version("program 1.2.3".to_string()), args.parse(&vec!("-V")).unwrap());
+        }
+    }
+    mod without_long_name {
+        use {Args,ErrorKind};
 
-                #[test]
-                #[allow(unused_must_use)]
-                fn returns_values() {
-                    let values = vec!["test", "value"];
-                    let mut args = args!(Occur::Multi, None);
-                    args.parse(&vec!("-o", values[0], "-o", values[1]));
+        #[test]
+        fn is_ignored() {
+            let mut args = args!();
+            args.version_flag("V", "", "Print the version", "1.2.3");
 
-                    let result = args.values_of::<String>("option");
-                    assert!(result.is_ok());
-                    let results = result.unwrap();
-                    assert_eq!(2, values.len());
-                    assert_eq!(values[0], results[0]);
-                    assert_eq!(values[1], results[1]);
-                }
-            }
+            assert_eq!(ErrorKind::UnrecognizedOption, args.parse(&vec!("-V")).unwrap_err().kind());
         }
     }
 }
@@ -356,13 +2588,127 @@ mod validated_value_of {
     }
 }
 
+mod man_page {
+    use Args;
+    use getopts::Occur;
+
+    fn args() -> Args {
+        let mut args = Args::new("program", "Run this program");
+        args.option("i", "iter", "The number of times to run", "TIMES", Occur::Req, None);
+        args.subcommand("build", "Build the project");
+        args.man_section("SEE ALSO", "cargo(1)");
+        args
+    }
+
+    #[test]
+    fn includes_synopsis() {
+        assert!(args().man_page().contains(".SH SYNOPSIS\n.B program\n\\-i TIMES COMMAND [ARGS]..\n"));
+    }
+
+    #[test]
+    fn includes_options() {
+        assert!(args().man_page()
+            .contains(".TP\n\\fB\\-i\\fR, \\fB\\-\\-iter\\fR \\fITIMES\\fR\nThe number of times to run [required]\n"));
+    }
+
+    #[test]
+    fn includes_commands() {
+        assert!(args().man_page().contains(".SH COMMANDS\n.TP\n\\fBbuild\\fR\nBuild the project\n"));
+    }
+
+    #[test]
+    fn ends_with_sections() {
+        assert!(args().man_page().ends_with(".SH SEE ALSO\ncargo(1)\n"));
+    }
+}
+
+mod maybe_value_of {
+    mod absent {
+        use {Args,MaybeValue};
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn returns_absent() {
+            let mut args = Args::new("program", "Run this program");
+            args.maybe_option("c", "color", "Color", "WHEN", Some("always".to_string()));
+            args.parse(&vec!(""));
+
+            assert_eq!(MaybeValue::Absent, args.maybe_value_of::<String>("color").unwrap());
+        }
+    }
+
+    mod valueless {
+        mod with_implicit {
+            use {Args,MaybeValue};
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_present_implicit() {
+                let mut args = Args::new("program", "Run this program");
+                args.maybe_option("c", "color", "Color", "WHEN", Some("always".to_string()));
+                args.parse(&vec!("--color"));
+
+                assert_eq!(MaybeValue::Present(Some("always".to_string())),
+                    args.maybe_value_of::<String>("color").unwrap());
+                assert_eq!("always".to_string(), args.value_of::<String>("color").unwrap());
+            }
+        }
+
+        mod without_implicit {
+            use {Args,MaybeValue};
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_present_none() {
+                let mut args = Args::new("program", "Run this program");
+                args.maybe_option("c", "color", "Color", "WHEN", None);
+                args.parse(&vec!("--color"));
+
+                assert_eq!(MaybeValue::Present(None), args.maybe_value_of::<String>("color").unwrap());
+                assert!(!args.has_value("color"));
+            }
+        }
+    }
+
+    mod present {
+        mod cannot_be_cast {
+            use Args;
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_err() {
+                let mut args = Args::new("program", "Run this program");
+                args.maybe_option("l", "level", "Level", "LEVEL", None);
+                args.parse(&vec!("--level=high"));
+
+                assert!(args.maybe_value_of::<u8>("level").is_err());
+            }
+        }
+
+        mod can_be_cast {
+            use {Args,MaybeValue};
+
+            #[test]
+            #[allow(unused_must_use)]
+            fn returns_value() {
+                let mut args = Args::new("program", "Run this program");
+                args.maybe_option("c", "color", "Color", "WHEN", Some("always".to_string()));
+                args.parse(&vec!("--color=never"));
+
+                assert_eq!(MaybeValue::Value("never".to_string()),
+                    args.maybe_value_of::<String>("color").unwrap());
+            }
+        }
+    }
+}
+
 mod optional_value_of {
     mod opt_absent {
         use Args;
 
         #[test]
         fn returns_none() {
-            assert!(!args!().optional_value_of::<i32>("").unwrap().is_some())
+            assert!(args!().optional_value_of::<i32>("").unwrap().is_none())
         }
     }
 
@@ -549,7 +2895,7 @@ mod values_of {
             #[test]
             #[allow(unused_must_use)]
             fn returns_ok_value() {
-                let values = vec!["0", "0"];
+                let values = ["0", "0"];
                 let mut args = args!(Occur::Multi, None);
                 args.parse(&vec!("-o", values[0], "-o", values[1]));
 
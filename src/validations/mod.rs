@@ -4,7 +4,7 @@ use std::fmt::{self,Display,Formatter};
 
 #[cfg(test)] mod tst;
 
-use super::ArgsError;
+use super::{ArgsError,ErrorKind};
 
 /// A trait designed to provide validation for command line argument parsing.
 pub trait Validation {
@@ -84,7 +84,7 @@ pub struct OrderValidation<O: Clone + Display + PartialOrd> {
 impl<O: Clone + Display + PartialOrd> OrderValidation<O> {
     /// Creates a new `OrderValidation` with the provided `order` and `bound`.
     pub fn new(order: Order, bound: O) -> OrderValidation<O> {
-        OrderValidation { bound: bound.clone(), order: order }
+        OrderValidation { bound: bound.clone(), order }
     }
 }
 
@@ -93,6 +93,7 @@ impl<O: Clone + Display + PartialOrd> Validation for OrderValidation<O> {
 
     fn error(&self, value: &O) -> ArgsError {
         ArgsError::new("order invalid", &format!("{} is not {} {}", value, self.order, self.bound))
+            .with_kind(ErrorKind::Validation)
     }
 
     fn is_valid(&self, value: &O) -> bool {